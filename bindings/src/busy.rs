@@ -0,0 +1,67 @@
+use std::ffi::c_void;
+use std::time::{Duration, Instant};
+
+use crate::host::HostPtr;
+
+/// Host callback consulted when the database is locked, like `sqlite3_busy_handler`.
+/// Receives the user data pointer and the number of times it has been invoked for the
+/// current step; returning non-zero retries the step, zero gives up with BUSY.
+pub type BusyHandlerCallback = extern "C" fn(user_data: *mut c_void, attempts: i32) -> i32;
+
+/// How a connection reacts to a step that returned `StepResult::Busy`.
+#[derive(Copy, Clone)]
+pub enum BusyPolicy {
+    /// Report BUSY immediately. This is the default.
+    Fail,
+    /// Back off and retry until the timeout has elapsed.
+    Timeout(Duration),
+    /// Ask the host whether to retry.
+    Handler {
+        callback: BusyHandlerCallback,
+        user_data: HostPtr,
+    },
+}
+
+// Same back-off schedule SQLite uses for sqlite3_busy_timeout
+const BACKOFF_DELAYS_MS: [u64; 12] = [1, 2, 5, 10, 15, 20, 25, 25, 25, 50, 50, 100];
+
+/// Retry state for a single step loop.
+pub struct BusyRetry {
+    policy: BusyPolicy,
+    attempts: u32,
+    started: Instant,
+}
+
+impl BusyRetry {
+    pub fn new(policy: BusyPolicy) -> Self {
+        Self {
+            policy,
+            attempts: 0,
+            started: Instant::now(),
+        }
+    }
+
+    /// Returns how long to wait before retrying the step, or None to give up with BUSY.
+    /// The caller waits, so it can release its locks and watch for an interrupt meanwhile.
+    pub fn retry_after(&mut self) -> Option<Duration> {
+        let attempts = self.attempts;
+        self.attempts = self.attempts.saturating_add(1);
+
+        match self.policy {
+            BusyPolicy::Fail => None,
+            BusyPolicy::Timeout(timeout) => {
+                let elapsed = self.started.elapsed();
+                if elapsed >= timeout {
+                    return None;
+                }
+                let index = (attempts as usize).min(BACKOFF_DELAYS_MS.len() - 1);
+                let delay = Duration::from_millis(BACKOFF_DELAYS_MS[index]);
+                Some(delay.min(timeout - elapsed))
+            }
+            BusyPolicy::Handler { callback, user_data } => {
+                let retry = callback(user_data.0, attempts.min(i32::MAX as u32) as i32) != 0;
+                retry.then_some(Duration::ZERO)
+            }
+        }
+    }
+}
@@ -0,0 +1,426 @@
+use std::ffi::{c_void, CString};
+use std::sync::Mutex;
+
+use turso_core::{Connection, Value};
+use turso_ext::{AggCtx, InitAggFunction, ScalarFunction, Value as ExtValue, ValueType};
+
+use crate::host::{self, HostPtr, NamedSlots};
+use crate::{Error, Result, TursoErrorCode};
+
+/// Host implementation of a scalar SQL function. Receives the user data pointer, a
+/// context to report the result through (`turso_result_*`), and the arguments as
+/// value handles readable with `turso_value_*`. Both are only valid during the call.
+pub type ScalarFunctionCallback =
+    extern "C" fn(user_data: *mut c_void, context: *mut c_void, argc: i32, argv: *const *const c_void);
+
+/// Host callback run for every row of an aggregate group. Takes the same arguments as
+/// [ScalarFunctionCallback]; state kept across rows belongs in `turso_aggregate_context`.
+pub type AggregateStepCallback =
+    extern "C" fn(user_data: *mut c_void, context: *mut c_void, argc: i32, argv: *const *const c_void);
+
+/// Host callback that reports the result of an aggregate group through `turso_result_*`.
+/// Runs once per group and is the last chance to release anything the group's
+/// `turso_aggregate_context` storage refers to. It also runs, with its result ignored,
+/// for a group whose step failed and for a group left unfinished when the statement is
+/// reset or finalized early.
+pub type AggregateFinalCallback = extern "C" fn(user_data: *mut c_void, context: *mut c_void);
+
+/// Where a host function call leaves its result.
+pub struct FunctionContext {
+    result: Value,
+    error: Option<String>,
+    // Group being computed when called for an aggregate, null for scalar functions
+    aggregate: *mut AggregateState,
+}
+
+impl FunctionContext {
+    /// A context for a single value, such as a virtual table column.
+    pub(crate) fn for_value() -> Self {
+        Self::new(std::ptr::null_mut())
+    }
+
+    fn new(aggregate: *mut AggregateState) -> Self {
+        Self {
+            result: Value::Null,
+            error: None,
+            aggregate,
+        }
+    }
+
+    pub(crate) fn set_result(&mut self, value: Value) {
+        self.result = value;
+        self.error = None;
+    }
+
+    pub(crate) fn set_error(&mut self, message: String) {
+        self.error = Some(message);
+    }
+
+    /// Returns the zero-initialized storage of the current aggregate group, allocating
+    /// `size` bytes on first use. Null for scalar functions, or when nothing has been
+    /// allocated and `size` is 0.
+    pub(crate) fn aggregate_storage(&mut self, size: usize) -> *mut c_void {
+        if self.aggregate.is_null() {
+            return std::ptr::null_mut();
+        }
+
+        let state = unsafe { &mut *self.aggregate };
+        if state.storage.is_empty() && size > 0 {
+            // Whole words keep the storage aligned for any host struct
+            state.storage = vec![0u64; size.div_ceil(8)];
+        }
+
+        if state.storage.is_empty() {
+            std::ptr::null_mut()
+        } else {
+            state.storage.as_mut_ptr() as *mut c_void
+        }
+    }
+}
+
+// Each distinct function name is handed one of a fixed set of trampolines, see
+// NamedSlots. Aggregates only need this for init; step and final find the registration
+// through the group state that init allocates.
+const MAX_FUNCTIONS: usize = 64;
+
+#[derive(Copy, Clone)]
+enum FunctionKind {
+    Scalar(ScalarFunctionCallback),
+    Aggregate {
+        step: AggregateStepCallback,
+        finalize: AggregateFinalCallback,
+    },
+}
+
+#[derive(Clone)]
+struct Registration {
+    name: String,
+    n_args: i32,
+    kind: FunctionKind,
+    user_data: HostPtr,
+}
+
+// State of one aggregate group, owned through AggCtx::state
+struct AggregateState {
+    slot: usize,
+    owner: usize,
+    storage: Vec<u64>,
+    error: Option<String>,
+}
+
+// An AggCtx handed to turso_core by init_aggregate. turso_core only copies the pointer
+// around and never frees it, and a statement reset or dropped before a group is
+// finalized never calls finalize_aggregate, so the bindings keep every group under the
+// statement that created it and free them all in release_groups.
+struct Group {
+    statement: u64,
+    ctx: *mut AggCtx,
+}
+
+// Only touched by the thread stepping the statement, or by release_groups once nothing
+// steps it anymore.
+unsafe impl Send for Group {}
+
+static FUNCTION_SLOTS: Mutex<NamedSlots<Registration, MAX_FUNCTIONS>> = Mutex::new(NamedSlots::new());
+static GROUPS: Mutex<Vec<Group>> = Mutex::new(Vec::new());
+
+macro_rules! trampolines {
+    ($($slot:literal)*) => {
+        const SCALAR_TRAMPOLINES: [ScalarFunction; MAX_FUNCTIONS] = [$({
+            unsafe extern "C" fn trampoline(argc: i32, argv: *const ExtValue) -> ExtValue {
+                call_scalar($slot, argc, argv)
+            }
+            trampoline
+        }),*];
+
+        const AGGREGATE_INIT_TRAMPOLINES: [InitAggFunction; MAX_FUNCTIONS] = [$({
+            unsafe extern "C" fn trampoline() -> *mut AggCtx {
+                init_aggregate($slot)
+            }
+            trampoline
+        }),*];
+    };
+}
+
+trampolines!(
+    0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15
+    16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31
+    32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47
+    48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63
+);
+
+/// Registers a scalar function on the connection. owner identifies the registering
+/// connection so that registering the same name again replaces the callback and
+/// [release_functions] can drop its registrations.
+pub(crate) fn register_scalar_function(
+    conn: &Connection,
+    owner: usize,
+    name: &str,
+    n_args: i32,
+    callback: ScalarFunctionCallback,
+    user_data: *mut c_void,
+) -> Result<()> {
+    let c_name = function_name(name)?;
+    let slot = claim_slot(owner, name, n_args, FunctionKind::Scalar(callback), user_data)?;
+
+    let api = conn._build_turso_ext();
+    let rc = unsafe { (api.register_scalar_function)(api.ctx, c_name.as_ptr(), SCALAR_TRAMPOLINES[slot]) };
+    conn._free_extension_ctx(api);
+
+    finish_registration(slot, owner, name, rc.is_ok())
+}
+
+/// Registers an aggregate function on the connection, see [register_scalar_function].
+pub(crate) fn register_aggregate_function(
+    conn: &Connection,
+    owner: usize,
+    name: &str,
+    n_args: i32,
+    step: AggregateStepCallback,
+    finalize: AggregateFinalCallback,
+    user_data: *mut c_void,
+) -> Result<()> {
+    let c_name = function_name(name)?;
+    let slot = claim_slot(owner, name, n_args, FunctionKind::Aggregate { step, finalize }, user_data)?;
+
+    let api = conn._build_turso_ext();
+    let rc = unsafe {
+        (api.register_aggregate_function)(
+            api.ctx,
+            c_name.as_ptr(),
+            n_args,
+            AGGREGATE_INIT_TRAMPOLINES[slot],
+            step_aggregate,
+            finalize_aggregate,
+        )
+    };
+    conn._free_extension_ctx(api);
+
+    finish_registration(slot, owner, name, rc.is_ok())
+}
+
+/// Drops every function registered by owner, freeing the trampolines of names no other
+/// connection uses. Calls that still reach them fail with an error instead of invoking
+/// a callback the host may have freed.
+pub(crate) fn release_functions(owner: usize) {
+    if let Ok(mut slots) = FUNCTION_SLOTS.lock() {
+        slots.release(owner);
+    }
+}
+
+/// Frees the aggregate groups created while stepping statement. A group that was never
+/// finalized still gets its final callback, with the result ignored, so the host can
+/// release its storage.
+pub(crate) fn release_groups(statement: u64) {
+    let released: Vec<Group> = match GROUPS.lock() {
+        Ok(mut groups) => {
+            let (released, kept) = std::mem::take(&mut *groups)
+                .into_iter()
+                .partition(|group| group.statement == statement);
+            *groups = kept;
+            released
+        }
+        Err(_) => return,
+    };
+
+    // The host code runs without the lock, since it may step other statements
+    for group in released {
+        let ctx = unsafe { Box::from_raw(group.ctx) };
+        if !ctx.state.is_null() {
+            let mut state = unsafe { Box::from_raw(ctx.state as *mut AggregateState) };
+            let _ = run_final(&mut state);
+        }
+    }
+}
+
+fn function_name(name: &str) -> Result<CString> {
+    CString::new(name).map_err(|_| Error::SqlExecutionFailure("Invalid function name".to_string()))
+}
+
+fn claim_slot(owner: usize, name: &str, n_args: i32, kind: FunctionKind, user_data: *mut c_void) -> Result<usize> {
+    let registration = Registration {
+        name: name.to_string(),
+        n_args,
+        kind,
+        user_data: HostPtr(user_data),
+    };
+
+    FUNCTION_SLOTS.lock()
+        .map_err(|e| Error::MutexError(e.to_string()))?
+        .claim(owner, name, registration)
+        .ok_or_else(|| Error::CoreError {
+            code: TursoErrorCode::Full,
+            message: format!("Cannot register more than {MAX_FUNCTIONS} distinct function names"),
+        })
+}
+
+fn finish_registration(slot: usize, owner: usize, name: &str, registered: bool) -> Result<()> {
+    if registered {
+        return Ok(());
+    }
+
+    if let Ok(mut slots) = FUNCTION_SLOTS.lock() {
+        slots.remove(slot, owner);
+    }
+    Err(Error::SqlExecutionFailure(format!("Failed to register function {name}")))
+}
+
+fn lookup(slot: usize, owner: usize) -> Option<Registration> {
+    FUNCTION_SLOTS.lock().ok()?.get(slot, owner)
+}
+
+fn check_arity(function: &Registration, argc: i32) -> Option<String> {
+    if function.n_args >= 0 && argc != function.n_args {
+        Some(format!("wrong number of arguments to function {}()", function.name))
+    } else {
+        None
+    }
+}
+
+pub(crate) fn collect_args(argc: i32, argv: *const ExtValue) -> Vec<Value> {
+    if argv.is_null() {
+        return Vec::new();
+    }
+
+    (0..argc.max(0) as usize)
+        .map(|i| from_ext_value(unsafe { &*argv.add(i) }))
+        .collect()
+}
+
+pub(crate) fn arg_handles(args: &[Value]) -> Vec<*const c_void> {
+    args.iter()
+        .map(|value| value as *const Value as *const c_void)
+        .collect()
+}
+
+pub(crate) fn context_result(context: FunctionContext) -> ExtValue {
+    match context.error {
+        Some(message) => ExtValue::error_with_message(message),
+        None => to_ext_value(context.result),
+    }
+}
+
+fn call_scalar(slot: usize, argc: i32, argv: *const ExtValue) -> ExtValue {
+    let Some(function) = lookup(slot, host::current_connection()) else {
+        return ExtValue::error_with_message("function is not registered on this connection".to_string());
+    };
+    let FunctionKind::Scalar(callback) = function.kind else {
+        return ExtValue::error_with_message(format!("{}() is no longer a scalar function", function.name));
+    };
+    if let Some(message) = check_arity(&function, argc) {
+        return ExtValue::error_with_message(message);
+    }
+
+    let args = collect_args(argc, argv);
+    let handles = arg_handles(&args);
+    let mut context = FunctionContext::for_value();
+    callback(function.user_data.0, &mut context as *mut FunctionContext as *mut c_void, args.len() as i32, handles.as_ptr());
+
+    context_result(context)
+}
+
+fn init_aggregate(slot: usize) -> *mut AggCtx {
+    let state = Box::new(AggregateState {
+        slot,
+        owner: host::current_connection(),
+        storage: Vec::new(),
+        error: None,
+    });
+    let ctx = Box::into_raw(Box::new(AggCtx {
+        state: Box::into_raw(state) as *mut c_void,
+    }));
+
+    if let Ok(mut groups) = GROUPS.lock() {
+        groups.push(Group {
+            statement: host::current_statement(),
+            ctx,
+        });
+    }
+    ctx
+}
+
+unsafe extern "C" fn step_aggregate(ctx: *mut AggCtx, argc: i32, argv: *const ExtValue) {
+    if ctx.is_null() || (*ctx).state.is_null() {
+        return;
+    }
+    let state = (*ctx).state as *mut AggregateState;
+
+    // Step cannot report errors, so the first one is kept for finalize
+    if (*state).error.is_some() {
+        return;
+    }
+    let Some(function) = lookup((*state).slot, (*state).owner) else {
+        return;
+    };
+    let FunctionKind::Aggregate { step, .. } = function.kind else {
+        return;
+    };
+    if let Some(message) = check_arity(&function, argc) {
+        (*state).error = Some(message);
+        return;
+    }
+
+    let args = collect_args(argc, argv);
+    let handles = arg_handles(&args);
+    let mut context = FunctionContext::new(state);
+    step(function.user_data.0, &mut context as *mut FunctionContext as *mut c_void, args.len() as i32, handles.as_ptr());
+
+    if context.error.is_some() {
+        (*state).error = context.error;
+    }
+}
+
+unsafe extern "C" fn finalize_aggregate(ctx: *mut AggCtx) -> ExtValue {
+    if ctx.is_null() || (*ctx).state.is_null() {
+        return ExtValue::null();
+    }
+
+    // Only the state is taken; the AggCtx itself stays with its statement until
+    // release_groups, so a second finalize of the same group finds nothing to free
+    let state = std::mem::replace(&mut (*ctx).state, std::ptr::null_mut());
+    let mut state = Box::from_raw(state as *mut AggregateState);
+
+    let context = match run_final(&mut state) {
+        Ok(context) => context,
+        Err(message) => return ExtValue::error_with_message(message),
+    };
+    match state.error.take() {
+        Some(message) => ExtValue::error_with_message(message),
+        None => context_result(context),
+    }
+}
+
+// Runs the host's final callback for a group, even one whose step failed, since it is
+// where the host releases what the group's storage refers to
+fn run_final(state: &mut AggregateState) -> std::result::Result<FunctionContext, String> {
+    let Some(function) = lookup(state.slot, state.owner) else {
+        return Err("function is not registered on this connection".to_string());
+    };
+    let FunctionKind::Aggregate { finalize, .. } = function.kind else {
+        return Err(format!("{}() is no longer an aggregate function", function.name));
+    };
+
+    let mut context = FunctionContext::new(state);
+    finalize(function.user_data.0, &mut context as *mut FunctionContext as *mut c_void);
+    Ok(context)
+}
+
+fn from_ext_value(value: &ExtValue) -> Value {
+    match value.value_type() {
+        ValueType::Integer => value.to_integer().map_or(Value::Null, Value::Integer),
+        ValueType::Float => value.to_float().map_or(Value::Null, Value::Float),
+        ValueType::Text => value.to_text().map_or(Value::Null, Value::from_text),
+        ValueType::Blob => value.to_blob().map_or(Value::Null, Value::from_blob),
+        _ => Value::Null,
+    }
+}
+
+fn to_ext_value(value: Value) -> ExtValue {
+    match value {
+        Value::Null => ExtValue::null(),
+        Value::Integer(i) => ExtValue::from_integer(i),
+        Value::Float(f) => ExtValue::from_float(f),
+        Value::Text(text) => ExtValue::from_text(text.as_str().to_string()),
+        Value::Blob(blob) => ExtValue::from_blob(blob),
+    }
+}
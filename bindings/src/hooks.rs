@@ -0,0 +1,357 @@
+use std::ffi::c_void;
+
+use crate::host::HostPtr;
+
+/// Host callback run before a transaction commits, like `sqlite3_commit_hook`.
+/// Returning non-zero turns the commit into a rollback. It is asked once per
+/// transaction, even if COMMIT is retried after BUSY, and the commit can still fail
+/// after it approved, so side effects belong in a [PostCommitHookCallback].
+pub type CommitHookCallback = extern "C" fn(user_data: *mut c_void) -> i32;
+
+/// Host callback run once a transaction has committed, after COMMIT succeeded.
+pub type PostCommitHookCallback = extern "C" fn(user_data: *mut c_void);
+
+/// Host callback run after a transaction rolls back, like `sqlite3_rollback_hook`.
+pub type RollbackHookCallback = extern "C" fn(user_data: *mut c_void);
+
+/// An installed hook and the user data it is called with.
+#[derive(Copy, Clone)]
+pub struct Hook<F> {
+    pub callback: F,
+    pub user_data: HostPtr,
+}
+
+/// What a statement means for transaction hooks and tracking, judged from its leading keywords.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StatementKind {
+    /// Changes the database or schema, so in autocommit mode it commits on its own.
+    Write,
+    /// BEGIN.
+    Begin,
+    /// COMMIT or END.
+    Commit,
+    /// ROLLBACK of the whole transaction, not ROLLBACK TO a savepoint.
+    Rollback,
+    /// Anything else, including reads, SAVEPOINT, RELEASE and VACUUM, which can't run
+    /// in a transaction.
+    Other,
+}
+
+// PRAGMAs that write the database header when given a value
+const WRITING_PRAGMAS: [&str; 4] = ["application_id", "incremental_vacuum", "schema_version", "user_version"];
+
+impl StatementKind {
+    pub fn classify(sql: &str) -> Self {
+        let mut keywords = Keywords { rest: sql };
+        let Some(first) = keywords.next() else {
+            return StatementKind::Other;
+        };
+
+        match first.to_ascii_uppercase().as_str() {
+            "INSERT" | "UPDATE" | "DELETE" | "REPLACE" | "CREATE" | "DROP" | "ALTER" | "REINDEX" | "ANALYZE" => {
+                StatementKind::Write
+            }
+            "WITH" => Self::classify_with(keywords.rest),
+            "PRAGMA" => Self::classify_pragma(keywords.rest),
+            "BEGIN" => StatementKind::Begin,
+            "COMMIT" | "END" => StatementKind::Commit,
+            "ROLLBACK" => {
+                let mut next = keywords.next();
+                if next.is_some_and(|word| word.eq_ignore_ascii_case("TRANSACTION")) {
+                    next = keywords.next();
+                }
+                if next.is_some_and(|word| word.eq_ignore_ascii_case("TO")) {
+                    StatementKind::Other
+                } else {
+                    StatementKind::Rollback
+                }
+            }
+            _ => StatementKind::Other,
+        }
+    }
+
+    // A statement led by common table expressions is whatever follows them: the first
+    // SELECT, VALUES, INSERT, UPDATE, DELETE or REPLACE outside their parentheses
+    fn classify_with(sql: &str) -> Self {
+        let bytes = sql.as_bytes();
+        let mut depth = 0usize;
+        let mut i = 0;
+
+        while i < bytes.len() {
+            if let Some(next) = skip_opaque(sql, i) {
+                i = next;
+                continue;
+            }
+            match bytes[i] {
+                b'(' => {
+                    depth += 1;
+                    i += 1;
+                }
+                b')' => {
+                    depth = depth.saturating_sub(1);
+                    i += 1;
+                }
+                c if c.is_ascii_alphabetic() || c == b'_' => {
+                    let end = word_end(sql, i);
+                    if depth == 0 {
+                        match sql[i..end].to_ascii_uppercase().as_str() {
+                            "INSERT" | "UPDATE" | "DELETE" | "REPLACE" => return StatementKind::Write,
+                            "SELECT" | "VALUES" => return StatementKind::Other,
+                            _ => {}
+                        }
+                    }
+                    i = end;
+                }
+                _ => i += 1,
+            }
+        }
+        StatementKind::Other
+    }
+
+    // PRAGMA [schema.]name, a write when it sets a pragma that changes the database
+    fn classify_pragma(sql: &str) -> Self {
+        let mut keywords = Keywords { rest: sql };
+        let Some(mut name) = keywords.next() else {
+            return StatementKind::Other;
+        };
+        if let Some(rest) = skip_blank(keywords.rest).strip_prefix('.') {
+            keywords.rest = rest;
+            match keywords.next() {
+                Some(qualified) => name = qualified,
+                None => return StatementKind::Other,
+            }
+        }
+
+        let writing = WRITING_PRAGMAS.iter().any(|pragma| pragma.eq_ignore_ascii_case(name));
+        let assigns = skip_blank(keywords.rest).starts_with(['=', '(']);
+        if writing && (assigns || name.eq_ignore_ascii_case("incremental_vacuum")) {
+            StatementKind::Write
+        } else {
+            StatementKind::Other
+        }
+    }
+}
+
+/// Splits a script into its statements, so each one can be classified and run on its
+/// own. Semicolons in literals, quoted names, comments and trigger bodies don't end a
+/// statement, and pieces holding nothing but whitespace and comments are dropped.
+pub fn split_statements(sql: &str) -> Vec<&str> {
+    let bytes = sql.as_bytes();
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    // Words seen in the current statement, and BEGIN or CASE blocks left open in a
+    // CREATE TRIGGER body
+    let mut words = 0;
+    let mut creates = false;
+    let mut trigger = false;
+    let mut depth = 0usize;
+
+    while i < bytes.len() {
+        if let Some(next) = skip_opaque(sql, i) {
+            i = next;
+            continue;
+        }
+        match bytes[i] {
+            b';' if depth == 0 => {
+                push_statement(&mut statements, &sql[start..=i]);
+                i += 1;
+                start = i;
+                (words, creates, trigger) = (0, false, false);
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                let end = word_end(sql, i);
+                let word = &sql[i..end];
+                if words == 0 {
+                    creates = word.eq_ignore_ascii_case("CREATE");
+                } else if creates && words <= 2 && word.eq_ignore_ascii_case("TRIGGER") {
+                    trigger = true;
+                } else if trigger && (word.eq_ignore_ascii_case("BEGIN") || word.eq_ignore_ascii_case("CASE")) {
+                    depth += 1;
+                } else if trigger && word.eq_ignore_ascii_case("END") {
+                    depth = depth.saturating_sub(1);
+                }
+                words += 1;
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+
+    push_statement(&mut statements, &sql[start..]);
+    statements
+}
+
+fn push_statement<'a>(statements: &mut Vec<&'a str>, statement: &'a str) {
+    let rest = skip_blank(statement);
+    if !rest.is_empty() && rest != ";" {
+        statements.push(statement);
+    }
+}
+
+// Returns the index just past the literal, quoted name or comment starting at i, or
+// None when there is none, so nothing in it is taken for a keyword or delimiter
+fn skip_opaque(sql: &str, i: usize) -> Option<usize> {
+    let bytes = sql.as_bytes();
+    match bytes[i] {
+        quote @ (b'\'' | b'"' | b'`') => Some(skip_quoted(bytes, i, quote)),
+        b'[' => Some(find_from(sql, i + 1, "]").map_or(bytes.len(), |end| end + 1)),
+        b'-' if bytes.get(i + 1) == Some(&b'-') => Some(find_from(sql, i, "\n").unwrap_or(bytes.len())),
+        b'/' if bytes.get(i + 1) == Some(&b'*') => {
+            Some(find_from(sql, i + 2, "*/").map_or(bytes.len(), |end| end + 2))
+        }
+        _ => None,
+    }
+}
+
+// Returns the index just past a literal or quoted name, where a doubled quote is an
+// escaped one
+fn skip_quoted(bytes: &[u8], open: usize, quote: u8) -> usize {
+    let mut i = open + 1;
+    while i < bytes.len() {
+        if bytes[i] == quote {
+            if bytes.get(i + 1) != Some(&quote) {
+                return i + 1;
+            }
+            i += 1;
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+// Returns the index just past the word starting at start
+fn word_end(sql: &str, start: usize) -> usize {
+    sql[start..]
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$'))
+        .map_or(sql.len(), |len| start + len)
+}
+
+fn find_from(sql: &str, from: usize, pattern: &str) -> Option<usize> {
+    sql[from..].find(pattern).map(|offset| from + offset)
+}
+
+// Skips leading whitespace and comments
+fn skip_blank(mut sql: &str) -> &str {
+    loop {
+        sql = sql.trim_start();
+        if let Some(comment) = sql.strip_prefix("--") {
+            sql = comment.find('\n').map_or("", |end| &comment[end..]);
+        } else if let Some(comment) = sql.strip_prefix("/*") {
+            sql = comment.find("*/").map_or("", |end| &comment[end + 2..]);
+        } else {
+            return sql;
+        }
+    }
+}
+
+// Yields the leading words of a statement, skipping whitespace and comments
+struct Keywords<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for Keywords<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.rest = skip_blank(self.rest);
+
+        let end = self.rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(self.rest.len());
+        if end == 0 {
+            return None;
+        }
+        let (word, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Some(word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_reads_the_leading_keyword() {
+        assert_eq!(StatementKind::classify("insert into t values (1)"), StatementKind::Write);
+        assert_eq!(StatementKind::classify("  -- note\n/* x */ DELETE FROM t"), StatementKind::Write);
+        assert_eq!(StatementKind::classify("REINDEX"), StatementKind::Write);
+        assert_eq!(StatementKind::classify("SELECT * FROM t"), StatementKind::Other);
+        assert_eq!(StatementKind::classify("VACUUM"), StatementKind::Other);
+        assert_eq!(StatementKind::classify("BEGIN IMMEDIATE"), StatementKind::Begin);
+        assert_eq!(StatementKind::classify("end transaction"), StatementKind::Commit);
+        assert_eq!(StatementKind::classify("ROLLBACK"), StatementKind::Rollback);
+        assert_eq!(StatementKind::classify("ROLLBACK TRANSACTION TO sp"), StatementKind::Other);
+        assert_eq!(StatementKind::classify(""), StatementKind::Other);
+    }
+
+    #[test]
+    fn classify_looks_past_common_table_expressions() {
+        let insert = "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 3) \
+                      INSERT INTO t SELECT x FROM n";
+        assert_eq!(StatementKind::classify(insert), StatementKind::Write);
+        assert_eq!(
+            StatementKind::classify("WITH old AS (SELECT id FROM t) DELETE FROM t WHERE id IN old"),
+            StatementKind::Write
+        );
+        assert_eq!(
+            StatementKind::classify(r#"WITH "update" AS (SELECT 'delete') SELECT * FROM "update""#),
+            StatementKind::Other
+        );
+        assert_eq!(
+            StatementKind::classify("WITH c AS (VALUES (1)) UPDATE t SET v = (SELECT * FROM c)"),
+            StatementKind::Write
+        );
+    }
+
+    #[test]
+    fn classify_treats_setting_a_header_pragma_as_a_write() {
+        assert_eq!(StatementKind::classify("PRAGMA user_version = 3"), StatementKind::Write);
+        assert_eq!(StatementKind::classify("pragma main.application_id(7)"), StatementKind::Write);
+        assert_eq!(StatementKind::classify("PRAGMA incremental_vacuum"), StatementKind::Write);
+        assert_eq!(StatementKind::classify("PRAGMA user_version"), StatementKind::Other);
+        assert_eq!(StatementKind::classify("PRAGMA cache_size = 100"), StatementKind::Other);
+        assert_eq!(StatementKind::classify("PRAGMA journal_mode = wal"), StatementKind::Other);
+    }
+
+    #[test]
+    fn split_ignores_semicolons_in_literals_and_quoted_names() {
+        let sql = "INSERT INTO t VALUES ('a;b', 'it''s;'); \
+                   SELECT [x;y], `z;w`, \"q;\"\"r\" FROM t;";
+        assert_eq!(
+            split_statements(sql),
+            vec![
+                "INSERT INTO t VALUES ('a;b', 'it''s;');",
+                " SELECT [x;y], `z;w`, \"q;\"\"r\" FROM t;",
+            ]
+        );
+    }
+
+    #[test]
+    fn split_ignores_semicolons_in_comments_and_drops_empty_pieces() {
+        let sql = "-- first; still a comment\nSELECT 1; /* a; b */ SELECT 2;; -- trailing;\n";
+        assert_eq!(
+            split_statements(sql),
+            vec!["-- first; still a comment\nSELECT 1;", " /* a; b */ SELECT 2;"]
+        );
+    }
+
+    #[test]
+    fn split_keeps_a_trigger_body_together() {
+        let trigger = "CREATE TRIGGER log AFTER INSERT ON t BEGIN \
+                       INSERT INTO audit VALUES (CASE WHEN new.v > 0 THEN 'up' ELSE 'down' END); \
+                       UPDATE counts SET n = n + 1; END;";
+        let sql = format!("{trigger} INSERT INTO t VALUES (1);");
+        assert_eq!(split_statements(&sql), vec![trigger, " INSERT INTO t VALUES (1);"]);
+    }
+
+    #[test]
+    fn split_keeps_a_trailing_statement_without_a_semicolon() {
+        assert_eq!(
+            split_statements("CREATE TABLE t (x); INSERT INTO t VALUES (1)"),
+            vec!["CREATE TABLE t (x);", " INSERT INTO t VALUES (1)"]
+        );
+        assert!(split_statements("  \n-- only a comment").is_empty());
+    }
+}
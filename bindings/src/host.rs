@@ -0,0 +1,182 @@
+use std::cell::Cell;
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// User data the host passes along with a callback, handed back unchanged on every call.
+#[derive(Copy, Clone)]
+pub struct HostPtr(pub *mut c_void);
+
+// The host owns the data behind the pointer and must keep it valid, and safe to use from
+// whichever thread the bindings call back on, for as long as the callback is installed.
+unsafe impl Send for HostPtr {}
+
+thread_local! {
+    // Connection this thread is preparing or stepping a statement on, 0 when there is none
+    static CURRENT_CONNECTION: Cell<usize> = const { Cell::new(0) };
+    // Statement this thread is stepping, 0 when it is only preparing or there is none
+    static CURRENT_STATEMENT: Cell<u64> = const { Cell::new(0) };
+}
+
+static NEXT_STATEMENT: AtomicU64 = AtomicU64::new(1);
+
+/// Makes owner the connection whose registrations trampolines on this thread dispatch
+/// to, and returns the previous one for [leave_connection]. Scopes nest when a callback
+/// uses another connection.
+pub(crate) fn enter_connection(owner: usize) -> usize {
+    CURRENT_CONNECTION.with(|current| current.replace(owner))
+}
+
+/// Restores the connection that was current before [enter_connection].
+pub(crate) fn leave_connection(previous: usize) {
+    CURRENT_CONNECTION.with(|current| current.set(previous));
+}
+
+pub(crate) fn current_connection() -> usize {
+    CURRENT_CONNECTION.with(Cell::get)
+}
+
+/// Hands out the id that aggregate groups created while stepping a statement are kept
+/// under until the statement is done, reset or finalized.
+pub(crate) fn next_statement_id() -> u64 {
+    NEXT_STATEMENT.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Makes statement the one this thread is stepping, and returns the previous one for
+/// [leave_statement].
+pub(crate) fn enter_statement(statement: u64) -> u64 {
+    CURRENT_STATEMENT.with(|current| current.replace(statement))
+}
+
+/// Restores the statement that was current before [enter_statement].
+pub(crate) fn leave_statement(previous: u64) {
+    CURRENT_STATEMENT.with(|current| current.set(previous));
+}
+
+pub(crate) fn current_statement() -> u64 {
+    CURRENT_STATEMENT.with(Cell::get)
+}
+
+/// Host registrations that turso_core only knows through a trampoline, since it keeps
+/// bare function pointers with no user data. Each distinct name takes one of N slots,
+/// each with its own trampoline, shared by every connection that registers the name;
+/// the trampoline finds the registration of the connection current on its thread.
+pub(crate) struct NamedSlots<T, const N: usize> {
+    slots: Vec<Option<Slot<T>>>,
+}
+
+struct Slot<T> {
+    name: String,
+    registrations: Vec<(usize, T)>,
+}
+
+impl<T: Clone, const N: usize> NamedSlots<T, N> {
+    pub(crate) const fn new() -> Self {
+        Self { slots: Vec::new() }
+    }
+
+    /// Records owner's registration under name, replacing an earlier one by the same
+    /// owner, and returns its slot. None when all N slots hold other names.
+    pub(crate) fn claim(&mut self, owner: usize, name: &str, value: T) -> Option<usize> {
+        let existing = self.slots.iter().position(|slot| {
+            slot.as_ref().is_some_and(|slot| slot.name.eq_ignore_ascii_case(name))
+        });
+
+        let index = match existing.or_else(|| self.slots.iter().position(Option::is_none)) {
+            Some(index) => index,
+            None if self.slots.len() < N => {
+                self.slots.push(None);
+                self.slots.len() - 1
+            }
+            None => return None,
+        };
+
+        let slot = self.slots[index].get_or_insert_with(|| Slot {
+            name: name.to_string(),
+            registrations: Vec::new(),
+        });
+        slot.registrations.retain(|(registered_by, _)| *registered_by != owner);
+        slot.registrations.push((owner, value));
+        Some(index)
+    }
+
+    /// Removes owner's registration from a slot, freeing the slot once nobody uses it.
+    pub(crate) fn remove(&mut self, index: usize, owner: usize) {
+        let Some(slot) = self.slots.get_mut(index).and_then(Option::as_mut) else {
+            return;
+        };
+
+        slot.registrations.retain(|(registered_by, _)| *registered_by != owner);
+        if slot.registrations.is_empty() {
+            self.slots[index] = None;
+        }
+    }
+
+    /// Removes every registration of owner.
+    pub(crate) fn release(&mut self, owner: usize) {
+        for index in 0..self.slots.len() {
+            self.remove(index, owner);
+        }
+    }
+
+    /// Copies owner's registration out of a slot, so no lock is held while host code runs.
+    pub(crate) fn get(&self, index: usize, owner: usize) -> Option<T> {
+        self.slots.get(index)?.as_ref()?
+            .registrations.iter()
+            .find(|(registered_by, _)| *registered_by == owner)
+            .map(|(_, value)| value.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owners_share_a_slot_by_name() {
+        let mut slots = NamedSlots::<&str, 2>::new();
+
+        let first = slots.claim(1, "double_it", "one").unwrap();
+        let second = slots.claim(2, "DOUBLE_IT", "two").unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(slots.get(first, 1), Some("one"));
+        assert_eq!(slots.get(first, 2), Some("two"));
+        assert_eq!(slots.get(first, 3), None);
+    }
+
+    #[test]
+    fn claiming_again_replaces_the_owners_registration() {
+        let mut slots = NamedSlots::<&str, 2>::new();
+
+        let slot = slots.claim(1, "f", "old").unwrap();
+        assert_eq!(slots.claim(1, "f", "new"), Some(slot));
+
+        assert_eq!(slots.get(slot, 1), Some("new"));
+    }
+
+    #[test]
+    fn slots_run_out_only_for_distinct_names() {
+        let mut slots = NamedSlots::<&str, 2>::new();
+
+        slots.claim(1, "a", "a").unwrap();
+        slots.claim(1, "b", "b").unwrap();
+
+        assert_eq!(slots.claim(1, "c", "c"), None);
+        assert!(slots.claim(2, "b", "b").is_some());
+    }
+
+    #[test]
+    fn a_slot_is_freed_once_every_owner_is_gone() {
+        let mut slots = NamedSlots::<&str, 2>::new();
+        let a = slots.claim(1, "a", "a").unwrap();
+        slots.claim(2, "a", "a").unwrap();
+        slots.claim(1, "b", "b").unwrap();
+
+        slots.remove(a, 1);
+        assert_eq!(slots.claim(1, "c", "c"), None);
+
+        slots.release(2);
+        assert_eq!(slots.get(a, 2), None);
+        assert_eq!(slots.claim(1, "c", "c"), Some(a));
+    }
+}
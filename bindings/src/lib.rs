@@ -1,12 +1,25 @@
+use std::cell::RefCell;
 use std::ffi::{c_char, c_void, CStr, CString};
 use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::num::NonZero;
 use turso_core::{Connection, Database, Value};
 
+pub mod busy;
+pub mod functions;
+pub mod hooks;
+pub mod host;
+pub mod maintenance;
 pub mod transaction;
+pub mod vtab;
 
-use transaction::TransactionBehavior;
+use busy::{BusyPolicy, BusyRetry};
+use functions::FunctionContext;
+use hooks::{CommitHookCallback, Hook, PostCommitHookCallback, RollbackHookCallback, StatementKind};
+use host::HostPtr;
+use maintenance::{AutoCheckpoint, AutoCheckpointConfig};
+use transaction::{DropBehavior, Transaction, TransactionBehavior};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -18,11 +31,53 @@ pub enum Error {
     SqlExecutionFailure(String),
     #[error("WAL operation error: `{0}`")]
     WalOperationError(String),
+    #[error("SQL execution failure: `{message}`")]
+    CoreError {
+        code: TursoErrorCode,
+        message: String,
+    },
+}
+
+impl Error {
+    fn busy() -> Self {
+        Error::CoreError {
+            code: TursoErrorCode::Busy,
+            message: "database is locked".to_string(),
+        }
+    }
+
+    fn misuse(message: &str) -> Self {
+        Error::CoreError {
+            code: TursoErrorCode::Misuse,
+            message: message.to_string(),
+        }
+    }
+
+    fn interrupted() -> Self {
+        Error::CoreError {
+            code: TursoErrorCode::Interrupt,
+            message: "interrupted".to_string(),
+        }
+    }
+
+    /// Extended result code describing this error.
+    pub fn code(&self) -> TursoErrorCode {
+        match self {
+            Error::ToSqlConversionFailure(_) => TursoErrorCode::Mismatch,
+            Error::MutexError(_) => TursoErrorCode::Internal,
+            Error::SqlExecutionFailure(_) => TursoErrorCode::Error,
+            Error::WalOperationError(_) => TursoErrorCode::IoErr,
+            Error::CoreError { code, .. } => *code,
+        }
+    }
 }
 
 impl From<turso_core::LimboError> for Error {
     fn from(err: turso_core::LimboError) -> Self {
-        Error::SqlExecutionFailure(err.to_string())
+        Error::CoreError {
+            code: TursoErrorCode::from_limbo_error(&err),
+            message: err.to_string(),
+        }
     }
 }
 
@@ -30,1336 +85,3362 @@ pub(crate) type BoxError = Box<dyn std::error::Error + Send + Sync>;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+// Result codes compatible with SQLite's primary and extended result codes.
+// Extended codes carry their primary code in the low byte.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TursoErrorCode {
+    Ok = 0,
+    Error = 1,
+    Internal = 2,
+    Perm = 3,
+    Abort = 4,
+    Busy = 5,
+    Locked = 6,
+    NoMem = 7,
+    ReadOnly = 8,
+    Interrupt = 9,
+    IoErr = 10,
+    Corrupt = 11,
+    NotFound = 12,
+    Full = 13,
+    CantOpen = 14,
+    Schema = 17,
+    TooBig = 18,
+    Constraint = 19,
+    Mismatch = 20,
+    Misuse = 21,
+    Range = 25,
+    NotADb = 26,
+    IoErrRead = 266,
+    IoErrWrite = 778,
+    IoErrLock = 3850,
+    ConstraintCheck = 275,
+    ConstraintForeignKey = 787,
+    ConstraintNotNull = 1299,
+    ConstraintPrimaryKey = 1555,
+    ConstraintUnique = 2067,
+    ConstraintCommitHook = 531,
+}
+
+impl TursoErrorCode {
+    /// Primary result code for an extended code, e.g. `Constraint` for `ConstraintUnique`.
+    pub fn primary(self) -> Self {
+        match self {
+            TursoErrorCode::IoErrRead | TursoErrorCode::IoErrWrite | TursoErrorCode::IoErrLock => {
+                TursoErrorCode::IoErr
+            }
+            TursoErrorCode::ConstraintCheck
+            | TursoErrorCode::ConstraintForeignKey
+            | TursoErrorCode::ConstraintNotNull
+            | TursoErrorCode::ConstraintPrimaryKey
+            | TursoErrorCode::ConstraintUnique
+            | TursoErrorCode::ConstraintCommitHook => TursoErrorCode::Constraint,
+            code => code,
+        }
+    }
+
+    fn from_limbo_error(err: &turso_core::LimboError) -> Self {
+        use turso_core::LimboError;
+
+        match err {
+            LimboError::Constraint(message) => Self::from_constraint_message(message),
+            LimboError::Busy => TursoErrorCode::Busy,
+            LimboError::LockingError(_) => TursoErrorCode::IoErrLock,
+            LimboError::ReadOnly => TursoErrorCode::ReadOnly,
+            LimboError::Interrupt => TursoErrorCode::Interrupt,
+            LimboError::Corrupt(_) => TursoErrorCode::Corrupt,
+            LimboError::NotADB => TursoErrorCode::NotADb,
+            LimboError::IOError(io_err) => match io_err.kind() {
+                std::io::ErrorKind::NotFound => TursoErrorCode::NotFound,
+                std::io::ErrorKind::PermissionDenied => TursoErrorCode::Perm,
+                std::io::ErrorKind::UnexpectedEof => TursoErrorCode::IoErrRead,
+                std::io::ErrorKind::WriteZero => TursoErrorCode::IoErrWrite,
+                _ => TursoErrorCode::IoErr,
+            },
+            LimboError::InternalError(_) => TursoErrorCode::Internal,
+            LimboError::InvalidArgument(_) => TursoErrorCode::Misuse,
+            LimboError::ConversionError(_) => TursoErrorCode::Mismatch,
+            _ => TursoErrorCode::Error,
+        }
+    }
+
+    // turso_core reports every constraint violation through a single variant, so the
+    // kind is recovered from the SQLite-compatible message ("UNIQUE constraint failed: ...").
+    fn from_constraint_message(message: &str) -> Self {
+        if message.contains("UNIQUE constraint failed") {
+            TursoErrorCode::ConstraintUnique
+        } else if message.contains("PRIMARY KEY constraint failed") {
+            TursoErrorCode::ConstraintPrimaryKey
+        } else if message.contains("NOT NULL constraint failed") {
+            TursoErrorCode::ConstraintNotNull
+        } else if message.contains("FOREIGN KEY constraint failed") {
+            TursoErrorCode::ConstraintForeignKey
+        } else if message.contains("CHECK constraint failed") {
+            TursoErrorCode::ConstraintCheck
+        } else {
+            TursoErrorCode::Constraint
+        }
+    }
+}
+
 // Error handling structure compatible with C#
 #[repr(C)]
 pub struct TursoFFIResult {
     pub success: bool,
     pub error_message: *mut c_char,
+    pub error_code: TursoErrorCode,
+    pub extended_error_code: TursoErrorCode,
 }
 
 impl TursoFFIResult {
     fn success() -> Self {
+        clear_last_error();
         Self {
             success: true,
             error_message: ptr::null_mut(),
+            error_code: TursoErrorCode::Ok,
+            extended_error_code: TursoErrorCode::Ok,
         }
     }
 
-    fn error(message: &str) -> Self {
+    fn error(code: TursoErrorCode, message: &str) -> Self {
+        set_last_error(code, message);
         let c_message = CString::new(message).unwrap_or_else(|_| CString::new("Invalid error message").unwrap());
         Self {
             success: false,
             error_message: c_message.into_raw(),
+            error_code: code.primary(),
+            extended_error_code: code,
         }
     }
 
+    fn misuse(message: &str) -> Self {
+        Self::error(TursoErrorCode::Misuse, message)
+    }
+
     fn from_result(result: Result<()>) -> Self {
         match result {
             Ok(()) => Self::success(),
-            Err(e) => Self::error(&e.to_string()),
+            Err(e) => Self::error(e.code(), &e.to_string()),
         }
     }
 }
 
-// Opaque wrapper for Database (following the target API structure)
-struct DatabaseWrapper {
-    database: Arc<Database>,
+// Outcome of a single step. Row and Done keep their historical values of 1 and 0;
+// for the failure statuses the detail is available through the last error accessors.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TursoStepStatus {
+    Error = -1,
+    Done = 0,
+    Row = 1,
+    Busy = 2,
+    Interrupted = 3,
+    Misuse = 4,
 }
 
-// Opaque wrapper for Connection
-struct ConnectionWrapper {
-    connection: Arc<Mutex<Arc<Connection>>>,
-    transaction_behavior: TransactionBehavior,
+impl TursoStepStatus {
+    fn from_result(result: std::thread::Result<Result<TursoStepStatus>>, panic_message: &str) -> Self {
+        match result {
+            Ok(Ok(status)) => {
+                clear_last_error();
+                status
+            }
+            Ok(Err(err)) => {
+                let code = err.code();
+                set_last_error(code, &err.to_string());
+                match code.primary() {
+                    TursoErrorCode::Busy => TursoStepStatus::Busy,
+                    TursoErrorCode::Interrupt => TursoStepStatus::Interrupted,
+                    TursoErrorCode::Misuse => TursoStepStatus::Misuse,
+                    _ => TursoStepStatus::Error,
+                }
+            }
+            Err(_) => {
+                set_last_error(TursoErrorCode::Internal, panic_message);
+                TursoStepStatus::Error
+            }
+        }
+    }
 }
 
-// Opaque wrapper for Statement
-struct StatementWrapper {
-    statement: Arc<Mutex<turso_core::Statement>>,
+// IO backend used to access the database file
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TursoIoBackend {
+    // Platform default; `:memory:` paths use the memory backend
+    Default = 0,
+    Memory = 1,
+    Syscall = 2,
+    IoUring = 3,
 }
 
-// Opaque wrapper for Rows
-struct RowsWrapper {
-    inner: Arc<Mutex<turso_core::Statement>>,
+impl TryFrom<i32> for TursoIoBackend {
+    type Error = Error;
+
+    fn try_from(value: i32) -> Result<Self> {
+        match value {
+            0 => Ok(TursoIoBackend::Default),
+            1 => Ok(TursoIoBackend::Memory),
+            2 => Ok(TursoIoBackend::Syscall),
+            3 => Ok(TursoIoBackend::IoUring),
+            _ => Err(Error::misuse("Invalid IO backend")),
+        }
+    }
 }
 
-// Helper function to check if we should use indexes
-fn indexes_enabled() -> bool {
-    #[cfg(feature = "experimental_indexes")]
-    return true;
-    #[cfg(not(feature = "experimental_indexes"))]
-    return false;
+// Options accepted by turso_database_open_with_options
+#[repr(C)]
+pub struct TursoOpenOptions {
+    pub read_only: bool,
+    pub create_if_missing: bool,
+    pub enable_mvcc: bool,
+    pub enable_indexes: bool,
+    // A TursoIoBackend value: 0 = Default, 1 = Memory, 2 = Syscall, 3 = IoUring
+    pub io_backend: i32,
 }
 
-// Database operations
-#[no_mangle]
-pub extern "C" fn turso_database_open_memory() -> *mut c_void {
-    let result = std::panic::catch_unwind(|| {
-        let io: Arc<dyn turso_core::IO> = Arc::new(turso_core::MemoryIO::new());
-        Database::open_file(io, ":memory:", false, indexes_enabled())
-    });
-    
-    match result {
-        Ok(Ok(database)) => {
-            let wrapper = Box::new(DatabaseWrapper { database });
-            Box::into_raw(wrapper) as *mut c_void
+impl Default for TursoOpenOptions {
+    fn default() -> Self {
+        Self {
+            read_only: false,
+            create_if_missing: true,
+            enable_mvcc: false,
+            enable_indexes: indexes_enabled(),
+            io_backend: TursoIoBackend::Default as i32,
         }
-        _ => ptr::null_mut(),
     }
 }
 
-#[no_mangle]
-pub extern "C" fn turso_database_open_file(path: *const c_char) -> *mut c_void {
-    if path.is_null() {
-        return ptr::null_mut();
+impl TursoOpenOptions {
+    fn open_flags(&self) -> turso_core::OpenFlags {
+        let mut flags = turso_core::OpenFlags::empty();
+        if self.read_only {
+            flags |= turso_core::OpenFlags::ReadOnly;
+        }
+        if self.create_if_missing {
+            flags |= turso_core::OpenFlags::Create;
+        }
+        flags
     }
+}
 
-    let result = std::panic::catch_unwind(|| {
-        let path = unsafe {
-            match CStr::from_ptr(path).to_str() {
-                Ok(s) => s,
-                Err(_) => return Err("Invalid path string"),
-            }
-        };
+// Options accepted by turso_database_start_auto_checkpoint
+#[repr(C)]
+pub struct TursoAutoCheckpointOptions {
+    // Checkpoint once this many WAL frames are waiting to be copied; 0 disables
+    pub wal_frame_threshold: u64,
+    // Checkpoint once WAL frames have been waiting this long; 0 disables
+    pub max_wal_age_ms: u64,
+    // How often the WAL is inspected; 0 uses one second
+    pub poll_interval_ms: u64,
+    // Checkpoint mode, as for turso_connection_checkpoint
+    pub mode: i32,
+}
 
-        let io: Arc<dyn turso_core::IO> = match path {
-            ":memory:" => Arc::new(turso_core::MemoryIO::new()),
-            _ => {
-                let io_result = turso_core::PlatformIO::new();
-                match io_result {
-                    Ok(platform_io) => Arc::new(platform_io),
-                    Err(_) => return Err("Failed to create platform IO"),
-                }
-            }
-        };
+// Callbacks of a virtual table module, see turso_connection_create_module. connect and
+// best_index receive the module's user data; the others receive the table or cursor
+// handle the host returned from connect or open.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct TursoVTabModule {
+    // Creates a table for CREATE VIRTUAL TABLE ... USING module(args). argv holds the
+    // module arguments as text values; the columns are declared with turso_vtab_declare.
+    // Returns the table handle, or null on failure.
+    pub connect: Option<extern "C" fn(user_data: *mut c_void, context: *mut c_void, argc: i32, argv: *const *const c_void) -> *mut c_void>,
+    // Optional. Picks a plan for a query; non-zero keeps the default full scan
+    pub best_index: Option<extern "C" fn(user_data: *mut c_void, constraints: *mut TursoIndexConstraint, n_constraints: i32, plan: *mut TursoIndexPlan) -> i32>,
+    // Optional. Releases a table handle once turso_core drops the table
+    pub disconnect: Option<extern "C" fn(table: *mut c_void)>,
+    pub open: Option<extern "C" fn(table: *mut c_void) -> *mut c_void>,
+    pub close: Option<extern "C" fn(cursor: *mut c_void)>,
+    // Starts a scan with the plan's idx_num and the values of the constraints given an
+    // argv_index, in argv_index order. Returns zero on success.
+    pub filter: Option<extern "C" fn(cursor: *mut c_void, idx_num: i32, argc: i32, argv: *const *const c_void) -> i32>,
+    // Moves to the next row. Returns zero on success.
+    pub next: Option<extern "C" fn(cursor: *mut c_void) -> i32>,
+    pub eof: Option<extern "C" fn(cursor: *mut c_void) -> bool>,
+    // Reports a column of the current row through turso_result_*
+    pub column: Option<extern "C" fn(cursor: *mut c_void, context: *mut c_void, column: i32)>,
+    pub rowid: Option<extern "C" fn(cursor: *mut c_void) -> i64>,
+}
 
-        Database::open_file(io, path, false, indexes_enabled())
-            .map_err(|_| "Failed to open database")
-    });
+// A WHERE constraint offered to a module's best_index callback
+#[repr(C)]
+pub struct TursoIndexConstraint {
+    pub column: i32,
+    // The operator, numbered like SQLite's SQLITE_INDEX_CONSTRAINT_* values
+    pub op: i32,
+    pub usable: bool,
+    // Set by the callback: 1-based position of the constraint's value in filter's
+    // argv, or 0 if the plan doesn't use it
+    pub argv_index: i32,
+    // Set by the callback: the module guarantees the constraint, so it isn't rechecked
+    pub omit: bool,
+}
+
+// The plan a module's best_index callback chose, prefilled with a full scan
+#[repr(C)]
+pub struct TursoIndexPlan {
+    // Passed to filter to say which plan was chosen
+    pub idx_num: i32,
+    pub estimated_cost: f64,
+    pub estimated_rows: i64,
+}
+
+// Per-thread record of the most recent failure. Functions that return a pointer can
+// only signal failure with null, so the reason is kept here for the caller to fetch.
+thread_local! {
+    static LAST_ERROR: RefCell<Option<(TursoErrorCode, String)>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(code: TursoErrorCode, message: &str) {
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = Some((code, message.to_string())));
+}
+
+fn clear_last_error() {
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = None);
+}
 
+// Unwraps the outcome of a pointer-returning FFI body, recording any failure
+fn record_outcome<T>(result: std::thread::Result<Result<T>>, panic_message: &str) -> Option<T> {
     match result {
-        Ok(Ok(database)) => {
-            let wrapper = Box::new(DatabaseWrapper { database });
-            Box::into_raw(wrapper) as *mut c_void
+        Ok(Ok(value)) => {
+            clear_last_error();
+            Some(value)
+        }
+        Ok(Err(err)) => {
+            set_last_error(err.code(), &err.to_string());
+            None
+        }
+        Err(_) => {
+            set_last_error(TursoErrorCode::Internal, panic_message);
+            None
         }
-        _ => ptr::null_mut(),
     }
 }
 
-#[no_mangle]
-pub extern "C" fn turso_database_close(database_ptr: *mut c_void) -> TursoFFIResult {
-    if database_ptr.is_null() {
-        return TursoFFIResult::error("Database pointer is null");
-    }
+// Opaque wrapper for Database (following the target API structure)
+struct DatabaseWrapper {
+    database: Arc<Database>,
+    io_backend: TursoIoBackend,
+    // Stopped when the wrapper is dropped, i.e. when the database is closed
+    auto_checkpoint: Mutex<Option<AutoCheckpoint>>,
+}
 
-    let result = std::panic::catch_unwind(|| {
-        unsafe {
-            let _database = Box::from_raw(database_ptr as *mut DatabaseWrapper);
-            // Database will be dropped automatically
+impl DatabaseWrapper {
+    fn new(database: Arc<Database>, io_backend: TursoIoBackend) -> Self {
+        Self {
+            database,
+            io_backend,
+            auto_checkpoint: Mutex::new(None),
         }
-        Ok(())
-    });
+    }
+}
 
-    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in database_close".to_string()))))
+// Opaque wrapper for Connection
+struct ConnectionWrapper {
+    connection: Arc<Mutex<Arc<Connection>>>,
+    state: Arc<ConnectionState>,
 }
 
-// Connection operations
-#[no_mangle]
-pub extern "C" fn turso_connection_open(database_ptr: *mut c_void) -> *mut c_void {
-    if database_ptr.is_null() {
-        return ptr::null_mut();
+impl ConnectionWrapper {
+    // The connection for work that steps statements. The lock is only held to clone it,
+    // so a step waiting on a busy database doesn't block other calls on the connection.
+    fn connection(&self) -> Result<Arc<Connection>> {
+        self.connection.lock()
+            .map(|conn| Arc::clone(&conn))
+            .map_err(|e| Error::MutexError(e.to_string()))
     }
+}
 
-    let result = std::panic::catch_unwind(|| {
-        let database = unsafe { &*(database_ptr as *const DatabaseWrapper) };
-        
-        database.database.connect().map(|connection| {
-            ConnectionWrapper {
-                connection: Arc::new(Mutex::new(connection)),
-                transaction_behavior: TransactionBehavior::Deferred,
-            }
-        })
-    });
+// Settings shared by a connection and the statements prepared from it. Kept outside
+// the connection mutex so they can be changed while another thread is stepping.
+struct ConnectionState {
+    busy_policy: Mutex<BusyPolicy>,
+    default_transaction_behavior: Mutex<TransactionBehavior>,
+    commit_hook: Mutex<Option<Hook<CommitHookCallback>>>,
+    post_commit_hook: Mutex<Option<Hook<PostCommitHookCallback>>>,
+    rollback_hook: Mutex<Option<Hook<RollbackHookCallback>>>,
+    extension_loading_enabled: AtomicBool,
+    interrupt_requested: AtomicBool,
+    // Step loops in progress plus statements stepped without finishing or being reset
+    running_statements: AtomicUsize,
+    // Bumped whenever the bindings see a transaction begin or end, so a transaction
+    // handle can tell whether the transaction it began is still the one open
+    transaction_generation: AtomicU64,
+    // Generation of the transaction the commit hook last approved, so a COMMIT retried
+    // after BUSY doesn't ask again
+    commit_approved: AtomicU64,
+}
 
-    match result {
-        Ok(Ok(wrapper)) => {
-            let boxed = Box::new(wrapper);
-            Box::into_raw(boxed) as *mut c_void
+impl ConnectionState {
+    fn new() -> Self {
+        Self {
+            busy_policy: Mutex::new(BusyPolicy::Fail),
+            default_transaction_behavior: Mutex::new(TransactionBehavior::Deferred),
+            commit_hook: Mutex::new(None),
+            post_commit_hook: Mutex::new(None),
+            rollback_hook: Mutex::new(None),
+            extension_loading_enabled: AtomicBool::new(false),
+            interrupt_requested: AtomicBool::new(false),
+            running_statements: AtomicUsize::new(0),
+            transaction_generation: AtomicU64::new(0),
+            commit_approved: AtomicU64::new(u64::MAX),
         }
-        _ => ptr::null_mut(),
     }
-}
 
-#[no_mangle]
-pub extern "C" fn turso_connection_close(connection_ptr: *mut c_void) -> TursoFFIResult {
-    if connection_ptr.is_null() {
-        return TursoFFIResult::error("Connection pointer is null");
+    // Marks a step loop as running until the returned guard is dropped
+    fn begin_step(&self, statement: u64) -> ActiveStep<'_> {
+        self.start_running();
+        ActiveStep {
+            state: self,
+            _scope: self.enter(),
+            _statement: StatementScope {
+                previous: host::enter_statement(statement),
+            },
+        }
     }
 
-    let result = std::panic::catch_unwind(|| {
-        unsafe {
-            let _connection = Box::from_raw(connection_ptr as *mut ConnectionWrapper);
-            // Connection will be dropped automatically
+    // Makes this the connection whose host functions and modules this thread calls
+    // while preparing or stepping, until the returned guard is dropped
+    fn enter(&self) -> ConnectionScope {
+        ConnectionScope {
+            previous: host::enter_connection(self as *const ConnectionState as usize),
         }
-        Ok(())
-    });
+    }
 
-    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in connection_close".to_string()))))
-}
+    fn start_running(&self) {
+        if self.running_statements.fetch_add(1, Ordering::AcqRel) == 0 {
+            // Drop an interrupt that raced with the last running statement finishing
+            self.interrupt_requested.store(false, Ordering::Release);
+        }
+    }
 
-#[no_mangle]
-pub extern "C" fn turso_connection_execute(
-    connection_ptr: *mut c_void,
-    sql: *const c_char,
-    rows_changed: *mut u64,
-) -> TursoFFIResult {
-    if connection_ptr.is_null() || sql.is_null() {
-        return TursoFFIResult::error("Invalid parameters");
+    fn end_step(&self) {
+        // Like sqlite3_interrupt, a pending interrupt only applies to work that was
+        // running when it was requested, so clear it once nothing is running
+        if self.running_statements.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.interrupt_requested.store(false, Ordering::Release);
+        }
     }
 
-    let result = std::panic::catch_unwind(|| {
-        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
-        
-        let sql_str = unsafe {
-            match CStr::from_ptr(sql).to_str() {
-                Ok(s) => s,
-                Err(_) => return Err(Error::SqlExecutionFailure("Invalid SQL string".to_string())),
-            }
-        };
+    // Asks the statement to stop if an interrupt was requested; the next step then
+    // reports StepResult::Interrupt
+    fn check_interrupt(&self, stmt: &mut turso_core::Statement) {
+        if self.interrupt_requested.load(Ordering::Acquire) {
+            stmt.interrupt();
+        }
+    }
 
-        let conn = connection_wrapper.connection.lock()
-            .map_err(|e| Error::MutexError(e.to_string()))?;
+    fn default_transaction_behavior(&self) -> Result<TransactionBehavior> {
+        self.default_transaction_behavior.lock()
+            .map(|behavior| *behavior)
+            .map_err(|e| Error::MutexError(e.to_string()))
+    }
 
-        let mut stmt = conn.prepare(sql_str)?;
-        
-        loop {
-            match stmt.step() {
-                Ok(turso_core::StepResult::Row) => {
-                    return Err(Error::SqlExecutionFailure(
-                        "unexpected row during execution".to_string(),
-                    ));
-                }
-                Ok(turso_core::StepResult::Done) => {
-                    let changes = stmt.n_change();
-                    if !rows_changed.is_null() {
-                        unsafe { *rows_changed = changes.max(0) as u64; }
-                    }
-                    return Ok(());
-                }
-                Ok(turso_core::StepResult::IO) => {
-                    stmt.run_once()?;
-                }
-                Ok(turso_core::StepResult::Busy) => {
-                    return Err(Error::SqlExecutionFailure("database is locked".to_string()));
-                }
-                Ok(turso_core::StepResult::Interrupt) => {
-                    return Err(Error::SqlExecutionFailure("interrupted".to_string()));
-                }
-                Err(err) => {
-                    return Err(err.into());
-                }
-            }
+    // A negative behavior selects the connection's default
+    fn transaction_behavior(&self, behavior: i32) -> Result<TransactionBehavior> {
+        if behavior < 0 {
+            self.default_transaction_behavior()
+        } else {
+            TransactionBehavior::try_from(behavior)
         }
-    });
+    }
 
-    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in connection_execute".to_string()))))
-}
+    fn transaction_generation(&self) -> u64 {
+        self.transaction_generation.load(Ordering::Acquire)
+    }
 
-// Query operations
-#[no_mangle]
-pub extern "C" fn turso_connection_query(
-    connection_ptr: *mut c_void,
-    sql: *const c_char,
-) -> *mut c_void {
-    if connection_ptr.is_null() || sql.is_null() {
-        return ptr::null_mut();
+    fn next_transaction_generation(&self) -> u64 {
+        self.transaction_generation.fetch_add(1, Ordering::AcqRel) + 1
     }
 
-    let result = std::panic::catch_unwind(|| {
-        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
-        
-        let sql_str = unsafe {
-            match CStr::from_ptr(sql).to_str() {
-                Ok(s) => s,
-                Err(_) => return Err("Invalid SQL string"),
-            }
-        };
+    fn commit_hook(&self) -> Option<Hook<CommitHookCallback>> {
+        self.commit_hook.lock().ok().and_then(|hook| *hook)
+    }
 
-        let conn = connection_wrapper.connection.lock()
-            .map_err(|_| "Failed to acquire connection lock")?;
+    fn post_commit_hook(&self) -> Option<Hook<PostCommitHookCallback>> {
+        self.post_commit_hook.lock().ok().and_then(|hook| *hook)
+    }
 
-        let stmt = conn.prepare(sql_str)
-            .map_err(|_| "Failed to prepare statement")?;
+    fn rollback_hook(&self) -> Option<Hook<RollbackHookCallback>> {
+        self.rollback_hook.lock().ok().and_then(|hook| *hook)
+    }
 
-        Ok(RowsWrapper {
-            inner: Arc::new(Mutex::new(stmt)),
-        })
-    });
+    fn has_transaction_hooks(&self) -> bool {
+        self.commit_hook().is_some() || self.post_commit_hook().is_some() || self.rollback_hook().is_some()
+    }
 
-    match result {
-        Ok(Ok(wrapper)) => {
-            let boxed = Box::new(wrapper);
-            Box::into_raw(boxed) as *mut c_void
+    fn busy_retry(&self) -> BusyRetry {
+        let policy = self.busy_policy.lock().map_or(BusyPolicy::Fail, |policy| *policy);
+        BusyRetry::new(policy)
+    }
+
+    // Sleeps before retrying a busy step, waking early once an interrupt is requested
+    // so the retried step reports it
+    fn wait_for_retry(&self, delay: std::time::Duration) {
+        let deadline = std::time::Instant::now() + delay;
+        while !self.interrupt_requested.load(Ordering::Acquire) {
+            let now = std::time::Instant::now();
+            if now >= deadline {
+                break;
+            }
+            std::thread::sleep((deadline - now).min(std::time::Duration::from_millis(1)));
         }
-        _ => ptr::null_mut(),
     }
 }
 
-#[no_mangle]
-pub extern "C" fn turso_connection_query_scalar_int(
-    connection_ptr: *mut c_void,
-    sql: *const c_char,
-    result: *mut i64,
-) -> TursoFFIResult {
-    if connection_ptr.is_null() || sql.is_null() || result.is_null() {
-        return TursoFFIResult::error("Invalid parameters");
+impl Drop for ConnectionState {
+    fn drop(&mut self) {
+        // Runs once the connection and every statement prepared from it are gone
+        functions::release_functions(self as *const ConnectionState as usize);
+        vtab::release_modules(self as *const ConnectionState as usize);
     }
+}
 
-    let query_result = std::panic::catch_unwind(|| {
-        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
-        
-        let sql_str = unsafe {
-            match CStr::from_ptr(sql).to_str() {
-                Ok(s) => s,
-                Err(_) => return Err(Error::SqlExecutionFailure("Invalid SQL string".to_string())),
-            }
-        };
+struct ActiveStep<'a> {
+    state: &'a ConnectionState,
+    _scope: ConnectionScope,
+    _statement: StatementScope,
+}
 
-        let conn = connection_wrapper.connection.lock()
-            .map_err(|e| Error::MutexError(e.to_string()))?;
+impl Drop for ActiveStep<'_> {
+    fn drop(&mut self) {
+        self.state.end_step();
+    }
+}
 
-        let mut stmt = conn.prepare(sql_str)?;
-        
-        loop {
-            match stmt.step() {
-                Ok(turso_core::StepResult::Row) => {
-                    if let Some(row) = stmt.row() {
-                        let value = row.get_value(0);
-                        match value {
-                            Value::Integer(i) => {
-                                unsafe { *result = *i };
-                                return Ok(());
-                            }
-                            _ => return Err(Error::SqlExecutionFailure("Expected integer value".to_string())),
-                        }
-                    } else {
-                        return Err(Error::SqlExecutionFailure("No row data available".to_string()));
-                    }
-                }
-                Ok(turso_core::StepResult::Done) => {
-                    return Err(Error::SqlExecutionFailure("No rows returned".to_string()));
-                }
-                Ok(turso_core::StepResult::IO) => {
-                    stmt.run_once()?;
-                }
-                Ok(turso_core::StepResult::Busy) => {
-                    return Err(Error::SqlExecutionFailure("database is locked".to_string()));
-                }
-                Ok(turso_core::StepResult::Interrupt) => {
-                    return Err(Error::SqlExecutionFailure("interrupted".to_string()));
-                }
-                Err(err) => {
-                    return Err(err.into());
-                }
-            }
+struct ConnectionScope {
+    previous: usize,
+}
+
+impl Drop for ConnectionScope {
+    fn drop(&mut self) {
+        host::leave_connection(self.previous);
+    }
+}
+
+// Ties the aggregate groups created while stepping to the statement, see
+// functions::release_groups
+struct StatementScope {
+    previous: u64,
+}
+
+impl Drop for StatementScope {
+    fn drop(&mut self) {
+        host::leave_statement(self.previous);
+    }
+}
+
+// Whether a statement has been stepped without finishing or being reset. Such a
+// statement counts as running, so an interrupt requested between two steps, e.g.
+// while the host reads a row, still stops it on the next step.
+#[derive(Default)]
+struct StatementProgress {
+    running: AtomicBool,
+}
+
+impl StatementProgress {
+    fn set_running(&self, state: &ConnectionState, running: bool) {
+        if self.running.swap(running, Ordering::AcqRel) == running {
+            return;
         }
-    });
+        if running {
+            state.start_running();
+        } else {
+            state.end_step();
+        }
+    }
+}
 
-    TursoFFIResult::from_result(query_result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in query_scalar_int".to_string()))))
+// Opaque wrapper for Statement
+struct StatementWrapper {
+    statement: Arc<Mutex<turso_core::Statement>>,
+    // Aggregate groups created while stepping are kept under this id, see host::next_statement_id
+    id: u64,
+    connection: Arc<Connection>,
+    state: Arc<ConnectionState>,
+    kind: StatementKind,
+    // Set while a write stepped in autocommit mode runs in its own transaction so
+    // the transaction hooks see it
+    implicit_transaction: AtomicBool,
+    progress: StatementProgress,
 }
 
-#[no_mangle]
-pub extern "C" fn turso_connection_query_scalar_string(
-    connection_ptr: *mut c_void,
-    sql: *const c_char,
-) -> *mut c_char {
-    if connection_ptr.is_null() || sql.is_null() {
-        return ptr::null_mut();
+impl StatementWrapper {
+    fn prepare(conn: &Arc<Connection>, state: &Arc<ConnectionState>, sql: &str) -> Result<Self> {
+        let statement = prepare_statement(conn, sql, state)?;
+
+        Ok(Self {
+            statement: Arc::new(Mutex::new(statement)),
+            id: host::next_statement_id(),
+            connection: conn.clone(),
+            state: state.clone(),
+            kind: StatementKind::classify(sql),
+            implicit_transaction: AtomicBool::new(false),
+            progress: StatementProgress::default(),
+        })
     }
 
-    let result = std::panic::catch_unwind(|| {
-        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
-        
-        let sql_str = unsafe {
-            match CStr::from_ptr(sql).to_str() {
-                Ok(s) => s,
-                Err(_) => return None,
+    // Steps the statement like execute_with_hooks runs one
+    fn step(&self) -> Result<TursoStepStatus> {
+        let state = &self.state;
+        match self.kind {
+            StatementKind::Commit if !self.connection.get_auto_commit() => {
+                commit_transaction(&self.connection, state)?;
+                return Ok(TursoStepStatus::Done);
             }
-        };
-
-        let conn = connection_wrapper.connection.lock().ok()?;
-        let mut stmt = conn.prepare(sql_str).ok()?;
-        
-        loop {
-            match stmt.step() {
-                Ok(turso_core::StepResult::Row) => {
-                    if let Some(row) = stmt.row() {
-                        let value = row.get_value(0);
-                        match value {
-                            Value::Text(s) => {
-                                let s_str = s.to_string();
-                                return CString::new(s_str).ok().map(|c| c.into_raw());
-                            }
-                            _ => return None,
-                        }
-                    } else {
-                        return None;
-                    }
-                }
-                Ok(turso_core::StepResult::Done) => {
-                    return None;
+            StatementKind::Rollback if !self.connection.get_auto_commit() => {
+                rollback_transaction(&self.connection, state)?;
+                return Ok(TursoStepStatus::Done);
+            }
+            StatementKind::Begin => {
+                let status = step_statement(&self.statement, self.id, state, &self.progress)?;
+                if status == TursoStepStatus::Done {
+                    state.next_transaction_generation();
                 }
-                Ok(turso_core::StepResult::IO) => {
-                    if stmt.run_once().is_err() {
-                        return None;
-                    }
-                    continue;
+                return Ok(status);
+            }
+            StatementKind::Write
+                if !self.implicit_transaction.load(Ordering::Acquire)
+                    && state.has_transaction_hooks()
+                    && self.connection.get_auto_commit() =>
+            {
+                begin_transaction(&self.connection, state.default_transaction_behavior()?, state)?;
+                self.implicit_transaction.store(true, Ordering::Release);
+            }
+            _ => {}
+        }
+
+        if !self.implicit_transaction.load(Ordering::Acquire) {
+            return step_statement(&self.statement, self.id, state, &self.progress);
+        }
+
+        match step_statement(&self.statement, self.id, state, &self.progress) {
+            Ok(TursoStepStatus::Done) => {
+                self.end_implicit_transaction()?;
+                Ok(TursoStepStatus::Done)
+            }
+            Err(err) if err.code() != TursoErrorCode::Busy => {
+                if self.implicit_transaction.swap(false, Ordering::AcqRel) {
+                    let _ = rollback_transaction(&self.connection, state);
                 }
-                _ => return None,
+                Err(err)
             }
+            status => status,
         }
-    });
+    }
 
-    result.unwrap_or(None).unwrap_or(ptr::null_mut())
-}
+    // Commits the implicit transaction, if one is open, e.g. when the statement is done
+    // or is reset or finalized before it finished
+    fn end_implicit_transaction(&self) -> Result<()> {
+        if !self.implicit_transaction.swap(false, Ordering::AcqRel) {
+            return Ok(());
+        }
 
-// Prepare statement
-#[no_mangle]
-pub extern "C" fn turso_connection_prepare(
-    connection_ptr: *mut c_void,
-    sql: *const c_char,
-) -> *mut c_void {
-    if connection_ptr.is_null() || sql.is_null() {
-        return ptr::null_mut();
+        commit_or_rollback(&self.connection, &self.state)
     }
+}
 
-    let result = std::panic::catch_unwind(|| {
-        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
-        
-        let sql_str = unsafe {
-            match CStr::from_ptr(sql).to_str() {
-                Ok(s) => s,
-                Err(_) => return Err("Invalid SQL string"),
+impl Drop for StatementWrapper {
+    fn drop(&mut self) {
+        if self.implicit_transaction.load(Ordering::Acquire) {
+            // A write stopped before it finished; the transaction can't end under it
+            if let Ok(mut stmt) = self.statement.lock() {
+                stmt.reset();
             }
-        };
+        }
+        functions::release_groups(self.id);
+        self.progress.set_running(&self.state, false);
+        // Nobody is left to hear about a failed commit, which is rolled back anyway
+        let _ = self.end_implicit_transaction();
+    }
+}
 
-        let conn = connection_wrapper.connection.lock()
-            .map_err(|_| "Failed to acquire connection lock")?;
+// Opaque wrapper for Rows, a statement the host only steps and reads
+struct RowsWrapper {
+    inner: StatementWrapper,
+}
 
-        let statement = conn.prepare(sql_str)
-            .map_err(|_| "Failed to prepare statement")?;
+// Opaque wrapper for Transaction
+struct TransactionWrapper {
+    transaction: Mutex<Transaction>,
+}
 
-        Ok(StatementWrapper {
-            statement: Arc::new(Mutex::new(statement)),
-        })
-    });
+// Helper function to check if we should use indexes
+fn indexes_enabled() -> bool {
+    #[cfg(feature = "experimental_indexes")]
+    return true;
+    #[cfg(not(feature = "experimental_indexes"))]
+    return false;
+}
 
-    match result {
-        Ok(Ok(wrapper)) => {
-            let boxed = Box::new(wrapper);
-            Box::into_raw(boxed) as *mut c_void
+// Steps a statement until it produces a row or finishes, driving any pending IO.
+// The statement stays running after a row, or after BUSY since it can be retried.
+fn step_statement(
+    statement: &Mutex<turso_core::Statement>,
+    id: u64,
+    state: &ConnectionState,
+    progress: &StatementProgress,
+) -> Result<TursoStepStatus> {
+    let mut busy_retry = state.busy_retry();
+
+    loop {
+        let mut stmt = statement.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+        let active_step = state.begin_step(id);
+
+        let result = step_until_row(&mut stmt, state);
+        let running = match &result {
+            Ok(status) => *status == TursoStepStatus::Row,
+            Err(err) => err.code() == TursoErrorCode::Busy,
+        };
+        progress.set_running(state, running);
+        drop(active_step);
+        drop(stmt);
+
+        match result {
+            Ok(TursoStepStatus::Done) => {
+                // Every aggregate group has been finalized by now
+                functions::release_groups(id);
+                return Ok(TursoStepStatus::Done);
+            }
+            // The statement is unlocked while waiting; it still counts as running, so an
+            // interrupt requested meanwhile stops the retry
+            Err(err) if err.code() == TursoErrorCode::Busy => match busy_retry.retry_after() {
+                Some(delay) => state.wait_for_retry(delay),
+                None => return Err(err),
+            },
+            result => return result,
         }
-        _ => ptr::null_mut(),
     }
 }
 
-// Rows operations
-#[no_mangle]
-pub extern "C" fn turso_rows_next(rows_ptr: *mut c_void) -> i32 {
-    if rows_ptr.is_null() {
-        return -1; // Error
+fn step_until_row(stmt: &mut turso_core::Statement, state: &ConnectionState) -> Result<TursoStepStatus> {
+    loop {
+        state.check_interrupt(stmt);
+        match stmt.step()? {
+            turso_core::StepResult::Row => return Ok(TursoStepStatus::Row),
+            turso_core::StepResult::Done => return Ok(TursoStepStatus::Done),
+            turso_core::StepResult::IO => {
+                stmt.run_once()?;
+            }
+            turso_core::StepResult::Busy => return Err(Error::busy()),
+            turso_core::StepResult::Interrupt => return Err(Error::interrupted()),
+        }
     }
+}
 
-    let result = std::panic::catch_unwind(|| {
-        let rows_wrapper = unsafe { &*(rows_ptr as *const RowsWrapper) };
-        
-        let mut stmt = rows_wrapper.inner.lock()
-            .map_err(|_| "Failed to acquire statement lock")?;
-        
-        loop {
-            match stmt.step() {
-                Ok(turso_core::StepResult::Row) => return Ok(1), // Row available
-                Ok(turso_core::StepResult::Done) => return Ok(0), // No more rows
-                Ok(turso_core::StepResult::IO) => {
-                    stmt.run_once().map_err(|_| "I/O error")?;
-                    continue;
-                }
-                Ok(turso_core::StepResult::Busy) => return Err("Database is locked"),
-                Ok(turso_core::StepResult::Interrupt) => return Err("Interrupted"),
-                Err(_) => return Err("Step failed"),
+// Runs a statement until it is done, discarding any rows it produces, and returns the
+// number of rows it changed
+fn run_statement(stmt: &mut turso_core::Statement, id: u64, state: &ConnectionState) -> Result<i64> {
+    let mut busy_retry = state.busy_retry();
+    let _active_step = state.begin_step(id);
+
+    loop {
+        state.check_interrupt(stmt);
+        match stmt.step()? {
+            turso_core::StepResult::Row => {}
+            turso_core::StepResult::Done => return Ok(stmt.n_change()),
+            turso_core::StepResult::IO => {
+                stmt.run_once()?;
             }
+            turso_core::StepResult::Busy => match busy_retry.retry_after() {
+                Some(delay) => state.wait_for_retry(delay),
+                None => return Err(Error::busy()),
+            },
+            turso_core::StepResult::Interrupt => return Err(Error::interrupted()),
         }
-    });
+    }
+}
 
-    result.unwrap_or(Err("Panic in rows_next")).unwrap_or(-1)
+// Prepares with the connection entered, since planning a query on a host virtual table
+// asks its module for the best index
+fn prepare_statement(conn: &Arc<Connection>, sql: &str, state: &ConnectionState) -> Result<turso_core::Statement> {
+    let _scope = state.enter();
+    Ok(conn.prepare(sql)?)
 }
 
-#[no_mangle]
-pub extern "C" fn turso_rows_close(rows_ptr: *mut c_void) -> TursoFFIResult {
-    if rows_ptr.is_null() {
-        return TursoFFIResult::error("Rows pointer is null");
+fn execute_sql(conn: &Arc<Connection>, sql: &str, state: &ConnectionState) -> Result<i64> {
+    let id = host::next_statement_id();
+    let mut stmt = prepare_statement(conn, sql, state)?;
+    let result = run_statement(&mut stmt, id, state);
+    functions::release_groups(id);
+    result
+}
+
+// Begins a transaction and returns its generation
+fn begin_transaction(
+    conn: &Arc<Connection>,
+    behavior: TransactionBehavior,
+    state: &ConnectionState,
+) -> Result<u64> {
+    execute_sql(conn, behavior.begin_sql(), state)?;
+    Ok(state.next_transaction_generation())
+}
+
+// Commits the open transaction, first giving the commit hook the chance to turn it
+// into a rollback, and tells the post-commit hook once COMMIT has succeeded
+fn commit_transaction(conn: &Arc<Connection>, state: &ConnectionState) -> Result<()> {
+    if conn.get_auto_commit() {
+        // No transaction to hook into; the engine reports the misplaced COMMIT
+        execute_sql(conn, "COMMIT", state)?;
+        return Ok(());
     }
 
-    let result = std::panic::catch_unwind(|| {
-        unsafe {
-            let _rows = Box::from_raw(rows_ptr as *mut RowsWrapper);
-            // Rows will be dropped automatically
+    let generation = state.transaction_generation();
+    if let Some(hook) = state.commit_hook() {
+        if state.commit_approved.load(Ordering::Acquire) != generation {
+            if (hook.callback)(hook.user_data.0) != 0 {
+                rollback_transaction(conn, state)?;
+                return Err(Error::CoreError {
+                    code: TursoErrorCode::ConstraintCommitHook,
+                    message: "Commit vetoed by commit hook".to_string(),
+                });
+            }
+            state.commit_approved.store(generation, Ordering::Release);
         }
-        Ok(())
-    });
+    }
 
-    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in rows_close".to_string()))))
+    execute_sql(conn, "COMMIT", state)?;
+    state.next_transaction_generation();
+    if let Some(hook) = state.post_commit_hook() {
+        (hook.callback)(hook.user_data.0);
+    }
+    Ok(())
 }
 
-#[no_mangle]
-pub extern "C" fn turso_statement_step(statement_ptr: *mut c_void) -> i32 {
-    if statement_ptr.is_null() {
-        return -1; // Error
+fn rollback_transaction(conn: &Arc<Connection>, state: &ConnectionState) -> Result<()> {
+    execute_sql(conn, "ROLLBACK", state)?;
+    state.next_transaction_generation();
+    if let Some(hook) = state.rollback_hook() {
+        (hook.callback)(hook.user_data.0);
     }
+    Ok(())
+}
 
-    let result = std::panic::catch_unwind(|| {
-        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
-        
-        let mut stmt = statement_wrapper.statement.lock()
-            .map_err(|_| "Failed to acquire statement lock")?;
-        
-        loop {
-            match stmt.step() {
-                Ok(turso_core::StepResult::Row) => return Ok(1), // Row available
-                Ok(turso_core::StepResult::Done) => return Ok(0), // No more rows
-                Ok(turso_core::StepResult::IO) => {
-                    stmt.run_once().map_err(|_| "I/O error")?;
-                    continue;
+// Ends a transaction the bindings opened on the caller's behalf. If the commit fails,
+// e.g. with BUSY, the transaction is rolled back rather than left open for a caller
+// that never started it.
+fn commit_or_rollback(conn: &Arc<Connection>, state: &ConnectionState) -> Result<()> {
+    let result = commit_transaction(conn, state);
+    if result.is_err() && !conn.get_auto_commit() {
+        let _ = rollback_transaction(conn, state);
+    }
+    result
+}
+
+// Runs a statement, routing COMMIT and ROLLBACK through the transaction hooks and
+// giving a write in autocommit mode its own transaction while hooks are installed
+fn execute_with_hooks(conn: &Arc<Connection>, sql: &str, state: &ConnectionState) -> Result<i64> {
+    match StatementKind::classify(sql) {
+        StatementKind::Commit if !conn.get_auto_commit() => {
+            commit_transaction(conn, state)?;
+            Ok(0)
+        }
+        StatementKind::Rollback if !conn.get_auto_commit() => {
+            rollback_transaction(conn, state)?;
+            Ok(0)
+        }
+        StatementKind::Begin => {
+            let changes = execute_sql(conn, sql, state)?;
+            state.next_transaction_generation();
+            Ok(changes)
+        }
+        StatementKind::Write if conn.get_auto_commit() && state.has_transaction_hooks() => {
+            begin_transaction(conn, state.default_transaction_behavior()?, state)?;
+            let changes = match execute_sql(conn, sql, state) {
+                Ok(changes) => changes,
+                Err(err) => {
+                    let _ = rollback_transaction(conn, state);
+                    return Err(err);
                 }
-                Ok(turso_core::StepResult::Busy) => return Err("Database is locked"),
-                Ok(turso_core::StepResult::Interrupt) => return Err("Interrupted"),
-                Err(_) => return Err("Step failed"),
-            }
+            };
+            commit_or_rollback(conn, state)?;
+            Ok(changes)
         }
-    });
-
-    result.unwrap_or(Err("Panic in statement_step")).unwrap_or(-1)
+        _ => execute_sql(conn, sql, state),
+    }
 }
 
-// Column operations for both Rows and Statement
-#[no_mangle]
-pub extern "C" fn turso_rows_column_count(rows_ptr: *mut c_void) -> i32 {
-    if rows_ptr.is_null() {
-        return -1;
+// Creates the requested IO backend and reports the one actually in use. io_uring
+// falls back to the syscall backend when it is not compiled in or cannot be set up,
+// e.g. in containers whose seccomp profile blocks io_uring_setup.
+fn create_io(backend: TursoIoBackend, path: &str) -> Result<(Arc<dyn turso_core::IO>, TursoIoBackend)> {
+    match backend {
+        TursoIoBackend::Default if path == ":memory:" => create_io(TursoIoBackend::Memory, path),
+        TursoIoBackend::Default => {
+            Ok((Arc::new(turso_core::PlatformIO::new()?), TursoIoBackend::Syscall))
+        }
+        TursoIoBackend::Memory => Ok((Arc::new(turso_core::MemoryIO::new()), TursoIoBackend::Memory)),
+        // turso_core only builds UnixIO for Linux and macOS; elsewhere, including Android
+        // and iOS, the platform backend is the syscall one
+        #[cfg(any(target_os = "linux", target_os = "macos"))]
+        TursoIoBackend::Syscall => Ok((Arc::new(turso_core::UnixIO::new()?), TursoIoBackend::Syscall)),
+        #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+        TursoIoBackend::Syscall => {
+            Ok((Arc::new(turso_core::PlatformIO::new()?), TursoIoBackend::Syscall))
+        }
+        #[cfg(all(target_os = "linux", feature = "io_uring"))]
+        TursoIoBackend::IoUring => match turso_core::UringIO::new() {
+            Ok(uring_io) => Ok((Arc::new(uring_io), TursoIoBackend::IoUring)),
+            Err(_) => create_io(TursoIoBackend::Syscall, path),
+        },
+        #[cfg(not(all(target_os = "linux", feature = "io_uring")))]
+        TursoIoBackend::IoUring => create_io(TursoIoBackend::Syscall, path),
     }
+}
 
+// Database operations
+#[no_mangle]
+pub extern "C" fn turso_database_open_memory() -> *mut c_void {
     let result = std::panic::catch_unwind(|| {
-        let rows_wrapper = unsafe { &*(rows_ptr as *const RowsWrapper) };
-        let stmt = rows_wrapper.inner.lock().ok()?;
-        Some(stmt.num_columns() as i32)
+        let io: Arc<dyn turso_core::IO> = Arc::new(turso_core::MemoryIO::new());
+        Ok(Database::open_file(io, ":memory:", false, indexes_enabled())?)
     });
-
-    result.unwrap_or(None).unwrap_or(-1)
+    
+    match record_outcome(result, "Panic in database_open_memory") {
+        Some(database) => {
+            let wrapper = Box::new(DatabaseWrapper::new(database, TursoIoBackend::Memory));
+            Box::into_raw(wrapper) as *mut c_void
+        }
+        None => ptr::null_mut(),
+    }
 }
 
 #[no_mangle]
-pub extern "C" fn turso_statement_column_count(statement_ptr: *mut c_void) -> i32 {
-    if statement_ptr.is_null() {
-        return -1;
+pub extern "C" fn turso_database_open_file(path: *const c_char) -> *mut c_void {
+    if path.is_null() {
+        set_last_error(TursoErrorCode::Misuse, "Path pointer is null");
+        return ptr::null_mut();
     }
 
     let result = std::panic::catch_unwind(|| {
-        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
-        let stmt = statement_wrapper.statement.lock().ok()?;
-        Some(stmt.num_columns() as i32)
+        let path = unsafe {
+            match CStr::from_ptr(path).to_str() {
+                Ok(s) => s,
+                Err(_) => return Err(Error::SqlExecutionFailure("Invalid path string".to_string())),
+            }
+        };
+
+        let (io, io_backend) = create_io(TursoIoBackend::Default, path)?;
+
+        let database = Database::open_file(io, path, false, indexes_enabled())?;
+        Ok((database, io_backend))
     });
 
-    result.unwrap_or(None).unwrap_or(-1)
+    match record_outcome(result, "Panic in database_open_file") {
+        Some((database, io_backend)) => {
+            let wrapper = Box::new(DatabaseWrapper::new(database, io_backend));
+            Box::into_raw(wrapper) as *mut c_void
+        }
+        None => ptr::null_mut(),
+    }
 }
 
+// Open a database with explicit options; a null options pointer uses the defaults
 #[no_mangle]
-pub extern "C" fn turso_rows_column_name(
-    rows_ptr: *mut c_void,
-    column_index: i32,
-) -> *mut c_char {
-    if rows_ptr.is_null() || column_index < 0 {
+pub extern "C" fn turso_database_open_with_options(
+    path: *const c_char,
+    options: *const TursoOpenOptions,
+) -> *mut c_void {
+    if path.is_null() {
+        set_last_error(TursoErrorCode::Misuse, "Path pointer is null");
         return ptr::null_mut();
     }
 
     let result = std::panic::catch_unwind(|| {
-        let rows_wrapper = unsafe { &*(rows_ptr as *const RowsWrapper) };
-        let stmt = rows_wrapper.inner.lock().ok()?;
-        let column_name = stmt.get_column_name(column_index as usize);
-        CString::new(column_name.as_ref()).ok().map(|c| c.into_raw())
+        let path = unsafe {
+            match CStr::from_ptr(path).to_str() {
+                Ok(s) => s,
+                Err(_) => return Err(Error::SqlExecutionFailure("Invalid path string".to_string())),
+            }
+        };
+
+        let default_options = TursoOpenOptions::default();
+        let options = if options.is_null() {
+            &default_options
+        } else {
+            unsafe { &*options }
+        };
+
+        let (io, io_backend) = create_io(TursoIoBackend::try_from(options.io_backend)?, path)?;
+
+        let database = Database::open_file_with_flags(
+            io,
+            path,
+            options.open_flags(),
+            options.enable_mvcc,
+            options.enable_indexes,
+        )?;
+        Ok((database, io_backend))
     });
 
-    result.unwrap_or(None).unwrap_or(ptr::null_mut())
+    match record_outcome(result, "Panic in database_open_with_options") {
+        Some((database, io_backend)) => {
+            let wrapper = Box::new(DatabaseWrapper::new(database, io_backend));
+            Box::into_raw(wrapper) as *mut c_void
+        }
+        None => ptr::null_mut(),
+    }
 }
 
 #[no_mangle]
-pub extern "C" fn turso_statement_column_name(
-    statement_ptr: *mut c_void,
-    column_index: i32,
-) -> *mut c_char {
-    if statement_ptr.is_null() || column_index < 0 {
-        return ptr::null_mut();
+pub extern "C" fn turso_database_close(database_ptr: *mut c_void) -> TursoFFIResult {
+    if database_ptr.is_null() {
+        return TursoFFIResult::misuse("Database pointer is null");
     }
 
     let result = std::panic::catch_unwind(|| {
-        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
-        let stmt = statement_wrapper.statement.lock().ok()?;
-        let column_name = stmt.get_column_name(column_index as usize);
-        CString::new(column_name.as_ref()).ok().map(|c| c.into_raw())
+        unsafe {
+            let _database = Box::from_raw(database_ptr as *mut DatabaseWrapper);
+            // Database will be dropped automatically
+        }
+        Ok(())
     });
 
-    result.unwrap_or(None).unwrap_or(ptr::null_mut())
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in database_close".to_string()))))
 }
 
+// Reports the IO backend the database ended up using, after any io_uring fallback
 #[no_mangle]
-pub extern "C" fn turso_rows_column_type(
-    rows_ptr: *mut c_void,
-    column_index: i32,
-) -> i32 {
-    if rows_ptr.is_null() || column_index < 0 {
-        return -1;
+pub extern "C" fn turso_database_get_io_backend(
+    database_ptr: *mut c_void,
+    result: *mut TursoIoBackend,
+) -> TursoFFIResult {
+    if database_ptr.is_null() || result.is_null() {
+        return TursoFFIResult::misuse("Invalid parameters");
     }
 
-    let result = std::panic::catch_unwind(|| {
-        let rows_wrapper = unsafe { &*(rows_ptr as *const RowsWrapper) };
-        let stmt = rows_wrapper.inner.lock().ok()?;
-        
-        if let Some(row) = stmt.row() {
+    let query_result = std::panic::catch_unwind(|| {
+        let database = unsafe { &*(database_ptr as *const DatabaseWrapper) };
+        unsafe { *result = database.io_backend };
+        Ok(())
+    });
+
+    TursoFFIResult::from_result(query_result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in get_io_backend".to_string()))))
+}
+
+// Connection operations
+#[no_mangle]
+pub extern "C" fn turso_connection_open(database_ptr: *mut c_void) -> *mut c_void {
+    if database_ptr.is_null() {
+        set_last_error(TursoErrorCode::Misuse, "Database pointer is null");
+        return ptr::null_mut();
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let database = unsafe { &*(database_ptr as *const DatabaseWrapper) };
+        
+        let connection = database.database.connect()?;
+        Ok(ConnectionWrapper {
+            connection: Arc::new(Mutex::new(connection)),
+            state: Arc::new(ConnectionState::new()),
+        })
+    });
+
+    match record_outcome(result, "Panic in connection_open") {
+        Some(wrapper) => {
+            let boxed = Box::new(wrapper);
+            Box::into_raw(boxed) as *mut c_void
+        }
+        None => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn turso_connection_close(connection_ptr: *mut c_void) -> TursoFFIResult {
+    if connection_ptr.is_null() {
+        return TursoFFIResult::misuse("Connection pointer is null");
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        unsafe {
+            let _connection = Box::from_raw(connection_ptr as *mut ConnectionWrapper);
+            // Connection will be dropped automatically
+        }
+        Ok(())
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in connection_close".to_string()))))
+}
+
+#[no_mangle]
+pub extern "C" fn turso_connection_execute(
+    connection_ptr: *mut c_void,
+    sql: *const c_char,
+    rows_changed: *mut u64,
+) -> TursoFFIResult {
+    if connection_ptr.is_null() || sql.is_null() {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+        
+        let sql_str = unsafe {
+            match CStr::from_ptr(sql).to_str() {
+                Ok(s) => s,
+                Err(_) => return Err(Error::SqlExecutionFailure("Invalid SQL string".to_string())),
+            }
+        };
+
+        let conn = connection_wrapper.connection()?;
+
+        // Rows from RETURNING clauses or status-reporting PRAGMAs are drained so the
+        // statement runs to completion
+        let changes = execute_with_hooks(&conn, sql_str, &connection_wrapper.state)?;
+        if !rows_changed.is_null() {
+            unsafe { *rows_changed = changes.max(0) as u64; }
+        }
+        Ok(())
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in connection_execute".to_string()))))
+}
+
+// Execute every statement in a script, in order. When `transactional` is set and the
+// connection is in autocommit mode the script runs inside one transaction, so a
+// failure leaves the database untouched. On failure `failed_statement_index` receives
+// the zero-based index of the statement that failed (-1 if the failure was not tied to
+// a statement); `rows_changed` receives the total changes of the statements that ran.
+// Each statement runs as turso_connection_execute would run it, so the transaction
+// hooks see the writes, COMMITs and ROLLBACKs in the script.
+#[no_mangle]
+pub extern "C" fn turso_connection_execute_batch(
+    connection_ptr: *mut c_void,
+    sql: *const c_char,
+    transactional: bool,
+    failed_statement_index: *mut i32,
+    rows_changed: *mut u64,
+) -> TursoFFIResult {
+    if connection_ptr.is_null() || sql.is_null() {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    if !failed_statement_index.is_null() {
+        unsafe { *failed_statement_index = -1; }
+    }
+    if !rows_changed.is_null() {
+        unsafe { *rows_changed = 0; }
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+        let state = &connection_wrapper.state;
+
+        let sql_str = unsafe {
+            match CStr::from_ptr(sql).to_str() {
+                Ok(s) => s,
+                Err(_) => return Err(Error::SqlExecutionFailure("Invalid SQL string".to_string())),
+            }
+        };
+
+        let conn = connection_wrapper.connection()?;
+
+        // The generation of the transaction wrapping the script, so it is only ended here
+        // if the script didn't end it itself
+        let implicit_transaction = if transactional && conn.get_auto_commit() {
+            Some(begin_transaction(&conn, state.default_transaction_behavior()?, state)?)
+        } else {
+            None
+        };
+        let still_open = |generation| state.transaction_generation() == generation && !conn.get_auto_commit();
+
+        let mut total_changes: u64 = 0;
+        let mut statement_index: i32 = 0;
+        let mut run_script = || -> Result<()> {
+            for statement in hooks::split_statements(sql_str) {
+                let changes = execute_with_hooks(&conn, statement, state)?;
+                total_changes += changes.max(0) as u64;
+                statement_index += 1;
+            }
+            Ok(())
+        };
+
+        match run_script() {
+            Ok(()) => {
+                if implicit_transaction.is_some_and(still_open) {
+                    commit_or_rollback(&conn, state)?;
+                }
+            }
+            Err(err) => {
+                if implicit_transaction.is_some_and(still_open) {
+                    let _ = rollback_transaction(&conn, state);
+                } else if !rows_changed.is_null() {
+                    unsafe { *rows_changed = total_changes; }
+                }
+                if !failed_statement_index.is_null() {
+                    unsafe { *failed_statement_index = statement_index; }
+                }
+                return Err(err);
+            }
+        }
+
+        if !rows_changed.is_null() {
+            unsafe { *rows_changed = total_changes; }
+        }
+        Ok(())
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in connection_execute_batch".to_string()))))
+}
+
+// Query operations
+#[no_mangle]
+pub extern "C" fn turso_connection_query(
+    connection_ptr: *mut c_void,
+    sql: *const c_char,
+) -> *mut c_void {
+    if connection_ptr.is_null() || sql.is_null() {
+        set_last_error(TursoErrorCode::Misuse, "Invalid parameters");
+        return ptr::null_mut();
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+        
+        let sql_str = unsafe {
+            match CStr::from_ptr(sql).to_str() {
+                Ok(s) => s,
+                Err(_) => return Err(Error::SqlExecutionFailure("Invalid SQL string".to_string())),
+            }
+        };
+
+        let conn = connection_wrapper.connection.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+
+        Ok(RowsWrapper {
+            inner: StatementWrapper::prepare(&conn, &connection_wrapper.state, sql_str)?,
+        })
+    });
+
+    match record_outcome(result, "Panic in connection_query") {
+        Some(wrapper) => {
+            let boxed = Box::new(wrapper);
+            Box::into_raw(boxed) as *mut c_void
+        }
+        None => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn turso_connection_query_scalar_int(
+    connection_ptr: *mut c_void,
+    sql: *const c_char,
+    result: *mut i64,
+) -> TursoFFIResult {
+    if connection_ptr.is_null() || sql.is_null() || result.is_null() {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    let query_result = std::panic::catch_unwind(|| {
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+        
+        let sql_str = unsafe {
+            match CStr::from_ptr(sql).to_str() {
+                Ok(s) => s,
+                Err(_) => return Err(Error::SqlExecutionFailure("Invalid SQL string".to_string())),
+            }
+        };
+
+        let conn = connection_wrapper.connection()?;
+
+        let statement = StatementWrapper::prepare(&conn, &connection_wrapper.state, sql_str)?;
+        if statement.step()? != TursoStepStatus::Row {
+            return Err(Error::SqlExecutionFailure("No rows returned".to_string()));
+        }
+
+        let stmt = statement.statement.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+        match stmt.row().map(|row| row.get_value(0)) {
+            Some(Value::Integer(i)) => {
+                unsafe { *result = *i };
+                Ok(())
+            }
+            Some(_) => Err(Error::SqlExecutionFailure("Expected integer value".to_string())),
+            None => Err(Error::SqlExecutionFailure("No row data available".to_string())),
+        }
+    });
+
+    TursoFFIResult::from_result(query_result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in query_scalar_int".to_string()))))
+}
+
+#[no_mangle]
+pub extern "C" fn turso_connection_query_scalar_string(
+    connection_ptr: *mut c_void,
+    sql: *const c_char,
+) -> *mut c_char {
+    if connection_ptr.is_null() || sql.is_null() {
+        set_last_error(TursoErrorCode::Misuse, "Invalid parameters");
+        return ptr::null_mut();
+    }
+
+    // Ok(None) covers "no row" and "not text", which return null without recording an error
+    let result = std::panic::catch_unwind(|| {
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+        
+        let sql_str = unsafe {
+            match CStr::from_ptr(sql).to_str() {
+                Ok(s) => s,
+                Err(_) => return Err(Error::SqlExecutionFailure("Invalid SQL string".to_string())),
+            }
+        };
+
+        let conn = connection_wrapper.connection()?;
+        let statement = StatementWrapper::prepare(&conn, &connection_wrapper.state, sql_str)?;
+        if statement.step()? != TursoStepStatus::Row {
+            return Ok(None);
+        }
+
+        let stmt = statement.statement.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+        match stmt.row().map(|row| row.get_value(0)) {
+            Some(Value::Text(s)) => Ok(CString::new(s.to_string()).ok().map(|c| c.into_raw())),
+            _ => Ok(None),
+        }
+    });
+
+    record_outcome(result, "Panic in query_scalar_string")
+        .flatten()
+        .unwrap_or(ptr::null_mut())
+}
+
+// Prepare statement
+#[no_mangle]
+pub extern "C" fn turso_connection_prepare(
+    connection_ptr: *mut c_void,
+    sql: *const c_char,
+) -> *mut c_void {
+    if connection_ptr.is_null() || sql.is_null() {
+        set_last_error(TursoErrorCode::Misuse, "Invalid parameters");
+        return ptr::null_mut();
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+        
+        let sql_str = unsafe {
+            match CStr::from_ptr(sql).to_str() {
+                Ok(s) => s,
+                Err(_) => return Err(Error::SqlExecutionFailure("Invalid SQL string".to_string())),
+            }
+        };
+
+        let conn = connection_wrapper.connection.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+
+        StatementWrapper::prepare(&conn, &connection_wrapper.state, sql_str)
+    });
+
+    match record_outcome(result, "Panic in connection_prepare") {
+        Some(wrapper) => {
+            let boxed = Box::new(wrapper);
+            Box::into_raw(boxed) as *mut c_void
+        }
+        None => ptr::null_mut(),
+    }
+}
+
+// Rows operations
+#[no_mangle]
+pub extern "C" fn turso_rows_next(rows_ptr: *mut c_void) -> TursoStepStatus {
+    if rows_ptr.is_null() {
+        set_last_error(TursoErrorCode::Misuse, "Rows pointer is null");
+        return TursoStepStatus::Misuse;
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let rows_wrapper = unsafe { &*(rows_ptr as *const RowsWrapper) };
+        rows_wrapper.inner.step()
+    });
+
+    TursoStepStatus::from_result(result, "Panic in rows_next")
+}
+
+#[no_mangle]
+pub extern "C" fn turso_rows_close(rows_ptr: *mut c_void) -> TursoFFIResult {
+    if rows_ptr.is_null() {
+        return TursoFFIResult::misuse("Rows pointer is null");
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        unsafe {
+            let _rows = Box::from_raw(rows_ptr as *mut RowsWrapper);
+            // Rows will be dropped automatically
+        }
+        Ok(())
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in rows_close".to_string()))))
+}
+
+#[no_mangle]
+pub extern "C" fn turso_statement_step(statement_ptr: *mut c_void) -> TursoStepStatus {
+    if statement_ptr.is_null() {
+        set_last_error(TursoErrorCode::Misuse, "Statement pointer is null");
+        return TursoStepStatus::Misuse;
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+        statement_wrapper.step()
+    });
+
+    TursoStepStatus::from_result(result, "Panic in statement_step")
+}
+
+// Column operations for both Rows and Statement
+#[no_mangle]
+pub extern "C" fn turso_rows_column_count(rows_ptr: *mut c_void) -> i32 {
+    if rows_ptr.is_null() {
+        return -1;
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let rows_wrapper = unsafe { &*(rows_ptr as *const RowsWrapper) };
+        let stmt = rows_wrapper.inner.statement.lock().ok()?;
+        Some(stmt.num_columns() as i32)
+    });
+
+    result.unwrap_or(None).unwrap_or(-1)
+}
+
+#[no_mangle]
+pub extern "C" fn turso_statement_column_count(statement_ptr: *mut c_void) -> i32 {
+    if statement_ptr.is_null() {
+        return -1;
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+        let stmt = statement_wrapper.statement.lock().ok()?;
+        Some(stmt.num_columns() as i32)
+    });
+
+    result.unwrap_or(None).unwrap_or(-1)
+}
+
+#[no_mangle]
+pub extern "C" fn turso_rows_column_name(
+    rows_ptr: *mut c_void,
+    column_index: i32,
+) -> *mut c_char {
+    if rows_ptr.is_null() || column_index < 0 {
+        return ptr::null_mut();
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let rows_wrapper = unsafe { &*(rows_ptr as *const RowsWrapper) };
+        let stmt = rows_wrapper.inner.statement.lock().ok()?;
+        let column_name = stmt.get_column_name(column_index as usize);
+        CString::new(column_name.as_ref()).ok().map(|c| c.into_raw())
+    });
+
+    result.unwrap_or(None).unwrap_or(ptr::null_mut())
+}
+
+#[no_mangle]
+pub extern "C" fn turso_statement_column_name(
+    statement_ptr: *mut c_void,
+    column_index: i32,
+) -> *mut c_char {
+    if statement_ptr.is_null() || column_index < 0 {
+        return ptr::null_mut();
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+        let stmt = statement_wrapper.statement.lock().ok()?;
+        let column_name = stmt.get_column_name(column_index as usize);
+        CString::new(column_name.as_ref()).ok().map(|c| c.into_raw())
+    });
+
+    result.unwrap_or(None).unwrap_or(ptr::null_mut())
+}
+
+#[no_mangle]
+pub extern "C" fn turso_rows_column_type(
+    rows_ptr: *mut c_void,
+    column_index: i32,
+) -> i32 {
+    if rows_ptr.is_null() || column_index < 0 {
+        return -1;
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let rows_wrapper = unsafe { &*(rows_ptr as *const RowsWrapper) };
+        let stmt = rows_wrapper.inner.statement.lock().ok()?;
+        
+        if let Some(row) = stmt.row() {
+            let value = row.get_value(column_index as usize);
+            match value {
+                Value::Null => Some(0),      // NULL
+                Value::Integer(_) => Some(1), // INTEGER
+                Value::Float(_) => Some(2),   // REAL/FLOAT
+                Value::Text(_) => Some(3),    // TEXT
+                Value::Blob(_) => Some(4),    // BLOB
+            }
+        } else {
+            None
+        }
+    });
+
+    result.unwrap_or(None).unwrap_or(-1)
+}
+
+#[no_mangle]
+pub extern "C" fn turso_statement_column_type(
+    statement_ptr: *mut c_void,
+    column_index: i32,
+) -> i32 {
+    if statement_ptr.is_null() || column_index < 0 {
+        return -1;
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+        let stmt = statement_wrapper.statement.lock().ok()?;
+        
+        if let Some(row) = stmt.row() {
+            let value = row.get_value(column_index as usize);
+            match value {
+                Value::Null => Some(0),      // NULL
+                Value::Integer(_) => Some(1), // INTEGER
+                Value::Float(_) => Some(2),   // REAL/FLOAT
+                Value::Text(_) => Some(3),    // TEXT
+                Value::Blob(_) => Some(4),    // BLOB
+            }
+        } else {
+            None
+        }
+    });
+
+    result.unwrap_or(None).unwrap_or(-1)
+}
+
+#[no_mangle]
+pub extern "C" fn turso_rows_column_int64(
+    rows_ptr: *mut c_void,
+    column_index: i32,
+) -> i64 {
+    if rows_ptr.is_null() || column_index < 0 {
+        return 0;
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let rows_wrapper = unsafe { &*(rows_ptr as *const RowsWrapper) };
+        let stmt = rows_wrapper.inner.statement.lock().ok()?;
+        
+        if let Some(row) = stmt.row() {
+            let value = row.get_value(column_index as usize);
+            match value {
+                Value::Integer(i) => Some(*i),
+                _ => Some(0),
+            }
+        } else {
+            Some(0)
+        }
+    });
+
+    result.unwrap_or(None).unwrap_or(0)
+}
+
+#[no_mangle]
+pub extern "C" fn turso_statement_column_int64(
+    statement_ptr: *mut c_void,
+    column_index: i32,
+) -> i64 {
+    if statement_ptr.is_null() || column_index < 0 {
+        return 0;
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+        let stmt = statement_wrapper.statement.lock().ok()?;
+        
+        if let Some(row) = stmt.row() {
+            let value = row.get_value(column_index as usize);
+            match value {
+                Value::Integer(i) => Some(*i),
+                _ => Some(0),
+            }
+        } else {
+            Some(0)
+        }
+    });
+
+    result.unwrap_or(None).unwrap_or(0)
+}
+
+#[no_mangle]
+pub extern "C" fn turso_rows_column_double(
+    rows_ptr: *mut c_void,
+    column_index: i32,
+) -> f64 {
+    if rows_ptr.is_null() || column_index < 0 {
+        return 0.0;
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let rows_wrapper = unsafe { &*(rows_ptr as *const RowsWrapper) };
+        let stmt = rows_wrapper.inner.statement.lock().ok()?;
+        
+        if let Some(row) = stmt.row() {
+            let value = row.get_value(column_index as usize);
+            match value {
+                Value::Float(f) => Some(*f),
+                Value::Integer(i) => Some(*i as f64),
+                _ => Some(0.0),
+            }
+        } else {
+            Some(0.0)
+        }
+    });
+
+    result.unwrap_or(None).unwrap_or(0.0)
+}
+
+#[no_mangle]
+pub extern "C" fn turso_statement_column_double(
+    statement_ptr: *mut c_void,
+    column_index: i32,
+) -> f64 {
+    if statement_ptr.is_null() || column_index < 0 {
+        return 0.0;
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+        let stmt = statement_wrapper.statement.lock().ok()?;
+        
+        if let Some(row) = stmt.row() {
+            let value = row.get_value(column_index as usize);
+            match value {
+                Value::Float(f) => Some(*f),
+                Value::Integer(i) => Some(*i as f64),
+                _ => Some(0.0),
+            }
+        } else {
+            Some(0.0)
+        }
+    });
+
+    result.unwrap_or(None).unwrap_or(0.0)
+}
+
+#[no_mangle]
+pub extern "C" fn turso_rows_column_text(
+    rows_ptr: *mut c_void,
+    column_index: i32,
+) -> *mut c_char {
+    if rows_ptr.is_null() || column_index < 0 {
+        return ptr::null_mut();
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let rows_wrapper = unsafe { &*(rows_ptr as *const RowsWrapper) };
+        let stmt = rows_wrapper.inner.statement.lock().ok()?;
+        
+        if let Some(row) = stmt.row() {
+            let value = row.get_value(column_index as usize);
+            let text = match value {
+                Value::Text(s) => s.to_string(),
+                Value::Integer(i) => i.to_string(),
+                Value::Float(f) => f.to_string(),
+                _ => return None,
+            };
+            CString::new(text).ok().map(|c| c.into_raw())
+        } else {
+            None
+        }
+    });
+
+    result.unwrap_or(None).unwrap_or(ptr::null_mut())
+}
+
+#[no_mangle]
+pub extern "C" fn turso_statement_column_text(
+    statement_ptr: *mut c_void,
+    column_index: i32,
+) -> *mut c_char {
+    if statement_ptr.is_null() || column_index < 0 {
+        return ptr::null_mut();
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+        let stmt = statement_wrapper.statement.lock().ok()?;
+        
+        if let Some(row) = stmt.row() {
+            let value = row.get_value(column_index as usize);
+            let text = match value {
+                Value::Text(s) => s.to_string(),
+                Value::Integer(i) => i.to_string(),
+                Value::Float(f) => f.to_string(),
+                _ => return None,
+            };
+            CString::new(text).ok().map(|c| c.into_raw())
+        } else {
+            None
+        }
+    });
+
+    result.unwrap_or(None).unwrap_or(ptr::null_mut())
+}
+
+#[no_mangle]
+pub extern "C" fn turso_rows_column_is_null(
+    rows_ptr: *mut c_void,
+    column_index: i32,
+) -> bool {
+    if rows_ptr.is_null() || column_index < 0 {
+        return true;
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let rows_wrapper = unsafe { &*(rows_ptr as *const RowsWrapper) };
+        let stmt = rows_wrapper.inner.statement.lock().ok()?;
+        
+        if let Some(row) = stmt.row() {
+            let value = row.get_value(column_index as usize);
+            Some(matches!(value, Value::Null))
+        } else {
+            Some(true)
+        }
+    });
+
+    result.unwrap_or(None).unwrap_or(true)
+}
+
+#[no_mangle]
+pub extern "C" fn turso_statement_column_is_null(
+    statement_ptr: *mut c_void,
+    column_index: i32,
+) -> bool {
+    if statement_ptr.is_null() || column_index < 0 {
+        return true;
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+        let stmt = statement_wrapper.statement.lock().ok()?;
+        
+        if let Some(row) = stmt.row() {
+            let value = row.get_value(column_index as usize);
+            Some(matches!(value, Value::Null))
+        } else {
+            Some(true)
+        }
+    });
+
+    result.unwrap_or(None).unwrap_or(true)
+}
+
+// Free a statement. A write stepped in autocommit mode but not to completion is
+// committed, or rolled back if the commit fails; reset it first to learn which.
+#[no_mangle]
+pub extern "C" fn turso_statement_finalize(statement_ptr: *mut c_void) -> TursoFFIResult {
+    if statement_ptr.is_null() {
+        return TursoFFIResult::misuse("Statement pointer is null");
+    }
+
+    // Dropping the statement ends its implicit transaction. It is freed even if that
+    // panics, so the handle is gone whatever happens and the call always succeeds.
+    let _ = std::panic::catch_unwind(|| {
+        unsafe { drop(Box::from_raw(statement_ptr as *mut StatementWrapper)) };
+    });
+
+    TursoFFIResult::success()
+}
+
+// Parameter binding operations
+#[no_mangle]
+pub extern "C" fn turso_statement_bind_int64(
+    statement_ptr: *mut c_void,
+    param_index: i32,
+    value: i64,
+) -> TursoFFIResult {
+    if statement_ptr.is_null() || param_index < 1 {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+        
+        match NonZero::new(param_index as usize) {
+            Some(index) => {
+                let mut stmt = statement_wrapper.statement.lock()
+                    .map_err(|e| Error::MutexError(e.to_string()))?;
+                stmt.bind_at(index, turso_core::Value::Integer(value));
+                Ok(())
+            }
+            None => Err(Error::SqlExecutionFailure("Parameter index must be greater than 0".to_string())),
+        }
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in statement_bind_int64".to_string()))))
+}
+
+#[no_mangle]
+pub extern "C" fn turso_statement_bind_double(
+    statement_ptr: *mut c_void,
+    param_index: i32,
+    value: f64,
+) -> TursoFFIResult {
+    if statement_ptr.is_null() || param_index < 1 {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+        
+        match NonZero::new(param_index as usize) {
+            Some(index) => {
+                let mut stmt = statement_wrapper.statement.lock()
+                    .map_err(|e| Error::MutexError(e.to_string()))?;
+                stmt.bind_at(index, turso_core::Value::Float(value));
+                Ok(())
+            }
+            None => Err(Error::SqlExecutionFailure("Parameter index must be greater than 0".to_string())),
+        }
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in statement_bind_double".to_string()))))
+}
+
+#[no_mangle]
+pub extern "C" fn turso_statement_bind_text(
+    statement_ptr: *mut c_void,
+    param_index: i32,
+    value: *const c_char,
+) -> TursoFFIResult {
+    if statement_ptr.is_null() || param_index < 1 || value.is_null() {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+        
+        let value_str = unsafe {
+            match CStr::from_ptr(value).to_str() {
+                Ok(s) => s,
+                Err(_) => return Err(Error::SqlExecutionFailure("Invalid UTF-8 string".to_string())),
+            }
+        };
+
+        match NonZero::new(param_index as usize) {
+            Some(index) => {
+                let mut stmt = statement_wrapper.statement.lock()
+                    .map_err(|e| Error::MutexError(e.to_string()))?;
+                stmt.bind_at(index, turso_core::Value::from_text(value_str));
+                Ok(())
+            }
+            None => Err(Error::SqlExecutionFailure("Parameter index must be greater than 0".to_string())),
+        }
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in statement_bind_text".to_string()))))
+}
+
+// Array binding. Turso has no carray() extension and no pointer values to pass an array
+// through, so the values are bound as JSON text in a single parameter, to be expanded
+// with the json_each table-valued function, e.g.
+// `SELECT * FROM users WHERE id IN (SELECT value FROM json_each(?))`, so one prepared
+// statement serves lists of any length.
+fn bind_json_array(statement_wrapper: &StatementWrapper, param_index: i32, json: String) -> Result<()> {
+    match NonZero::new(param_index as usize) {
+        Some(index) => {
+            let mut stmt = statement_wrapper.statement.lock()
+                .map_err(|e| Error::MutexError(e.to_string()))?;
+            stmt.bind_at(index, turso_core::Value::from_text(&json));
+            Ok(())
+        }
+        None => Err(Error::SqlExecutionFailure("Parameter index must be greater than 0".to_string())),
+    }
+}
+
+fn push_json_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+#[no_mangle]
+pub extern "C" fn turso_statement_bind_int64_array(
+    statement_ptr: *mut c_void,
+    param_index: i32,
+    values: *const i64,
+    count: i32,
+) -> TursoFFIResult {
+    if statement_ptr.is_null() || param_index < 1 || count < 0 || (values.is_null() && count > 0) {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+
+        let values = if count == 0 {
+            &[][..]
+        } else {
+            unsafe { std::slice::from_raw_parts(values, count as usize) }
+        };
+        let items: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+        bind_json_array(statement_wrapper, param_index, format!("[{}]", items.join(",")))
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in statement_bind_int64_array".to_string()))))
+}
+
+// NaN and infinities have no JSON representation and are bound as NULL elements
+#[no_mangle]
+pub extern "C" fn turso_statement_bind_double_array(
+    statement_ptr: *mut c_void,
+    param_index: i32,
+    values: *const f64,
+    count: i32,
+) -> TursoFFIResult {
+    if statement_ptr.is_null() || param_index < 1 || count < 0 || (values.is_null() && count > 0) {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+
+        let values = if count == 0 {
+            &[][..]
+        } else {
+            unsafe { std::slice::from_raw_parts(values, count as usize) }
+        };
+        // Debug formatting keeps the decimal point, so json_each reports REAL values
+        let items: Vec<String> = values.iter()
+            .map(|value| if value.is_finite() { format!("{value:?}") } else { "null".to_string() })
+            .collect();
+        bind_json_array(statement_wrapper, param_index, format!("[{}]", items.join(",")))
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in statement_bind_double_array".to_string()))))
+}
+
+// Null entries in values are bound as NULL elements
+#[no_mangle]
+pub extern "C" fn turso_statement_bind_text_array(
+    statement_ptr: *mut c_void,
+    param_index: i32,
+    values: *const *const c_char,
+    count: i32,
+) -> TursoFFIResult {
+    if statement_ptr.is_null() || param_index < 1 || count < 0 || (values.is_null() && count > 0) {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+
+        let values = if count == 0 {
+            &[][..]
+        } else {
+            unsafe { std::slice::from_raw_parts(values, count as usize) }
+        };
+
+        let mut json = String::from("[");
+        for (i, value) in values.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            if value.is_null() {
+                json.push_str("null");
+                continue;
+            }
+            match unsafe { CStr::from_ptr(*value) }.to_str() {
+                Ok(s) => push_json_string(&mut json, s),
+                Err(_) => return Err(Error::SqlExecutionFailure("Invalid UTF-8 string".to_string())),
+            }
+        }
+        json.push(']');
+
+        bind_json_array(statement_wrapper, param_index, json)
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in statement_bind_text_array".to_string()))))
+}
+
+#[no_mangle]
+pub extern "C" fn turso_statement_reset(statement_ptr: *mut c_void) -> TursoFFIResult {
+    if statement_ptr.is_null() {
+        return TursoFFIResult::misuse("Statement pointer is null");
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+        let mut stmt = statement_wrapper.statement.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+        stmt.reset();
+        drop(stmt);
+        functions::release_groups(statement_wrapper.id);
+        statement_wrapper.progress.set_running(&statement_wrapper.state, false);
+        statement_wrapper.end_implicit_transaction()
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in statement_reset".to_string()))))
+}
+
+// Rows changed by the statement, meaningful once turso_statement_step has returned Done
+#[no_mangle]
+pub extern "C" fn turso_statement_changes(
+    statement_ptr: *mut c_void,
+    result: *mut i64,
+) -> TursoFFIResult {
+    if statement_ptr.is_null() || result.is_null() {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    let query_result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+        let stmt = statement_wrapper.statement.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+        unsafe { *result = stmt.n_change() };
+        Ok(())
+    });
+
+    TursoFFIResult::from_result(query_result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in statement_changes".to_string()))))
+}
+
+// Rowid of the most recent INSERT on the statement's connection, so an INSERT stepped
+// to Done can report its rowid without a follow-up query
+#[no_mangle]
+pub extern "C" fn turso_statement_last_insert_rowid(
+    statement_ptr: *mut c_void,
+    result: *mut i64,
+) -> TursoFFIResult {
+    if statement_ptr.is_null() || result.is_null() {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    let query_result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+        unsafe { *result = statement_wrapper.connection.last_insert_rowid() };
+        Ok(())
+    });
+
+    TursoFFIResult::from_result(query_result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in statement_last_insert_rowid".to_string()))))
+}
+
+// Blob binding operations
+#[no_mangle]
+pub extern "C" fn turso_statement_bind_blob(
+    statement_ptr: *mut c_void,
+    param_index: i32,
+    data: *const u8,
+    data_len: i32,
+) -> TursoFFIResult {
+    if statement_ptr.is_null() || param_index < 1 || data_len < 0 {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    // Allow null data pointer only if data_len is 0 (empty blob)
+    if data.is_null() && data_len > 0 {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+        
+        let blob_data = if data_len == 0 {
+            Vec::new() // Empty blob
+        } else {
+            unsafe {
+                std::slice::from_raw_parts(data, data_len as usize).to_vec()
+            }
+        };
+
+        match NonZero::new(param_index as usize) {
+            Some(index) => {
+                let mut stmt = statement_wrapper.statement.lock()
+                    .map_err(|e| Error::MutexError(e.to_string()))?;
+                stmt.bind_at(index, turso_core::Value::from_blob(blob_data));
+                Ok(())
+            }
+            None => Err(Error::SqlExecutionFailure("Parameter index must be greater than 0".to_string())),
+        }
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in statement_bind_blob".to_string()))))
+}
+
+#[no_mangle]
+pub extern "C" fn turso_rows_column_blob(
+    rows_ptr: *mut c_void,
+    column_index: i32,
+    data_len: *mut i32,
+) -> *mut u8 {
+    if rows_ptr.is_null() || column_index < 0 || data_len.is_null() {
+        return ptr::null_mut();
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let rows_wrapper = unsafe { &*(rows_ptr as *const RowsWrapper) };
+        let stmt = rows_wrapper.inner.statement.lock().ok()?;
+        
+        if let Some(row) = stmt.row() {
             let value = row.get_value(column_index as usize);
             match value {
-                Value::Null => Some(0),      // NULL
-                Value::Integer(_) => Some(1), // INTEGER
-                Value::Float(_) => Some(2),   // REAL/FLOAT
-                Value::Text(_) => Some(3),    // TEXT
-                Value::Blob(_) => Some(4),    // BLOB
+                turso_core::Value::Blob(blob) => {
+                    let blob_len = blob.len() as i32;
+                    unsafe { *data_len = blob_len };
+                    
+                    if blob.is_empty() {
+                        return Some(ptr::null_mut());
+                    }
+                    
+                    // Allocate memory for the blob data
+                    let layout = std::alloc::Layout::array::<u8>(blob.len()).ok()?;
+                    let ptr = unsafe { std::alloc::alloc(layout) };
+                    if ptr.is_null() {
+                        unsafe { *data_len = 0 };
+                        return None;
+                    }
+                    
+                    // Copy blob data to allocated memory
+                    unsafe {
+                        std::ptr::copy_nonoverlapping(blob.as_ptr(), ptr, blob.len());
+                    }
+                    
+                    Some(ptr)
+                }
+                _ => {
+                    unsafe { *data_len = 0 };
+                    None
+                }
+            }
+        } else {
+            unsafe { *data_len = 0 };
+            None
+        }
+    });
+
+    result.unwrap_or(None).unwrap_or_else(|| {
+        unsafe { *data_len = 0; }
+        ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn turso_statement_column_blob(
+    statement_ptr: *mut c_void,
+    column_index: i32,
+    data_len: *mut i32,
+) -> *mut u8 {
+    if statement_ptr.is_null() || column_index < 0 || data_len.is_null() {
+        return ptr::null_mut();
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+        let stmt = statement_wrapper.statement.lock().ok()?;
+        
+        if let Some(row) = stmt.row() {
+            let value = row.get_value(column_index as usize);
+            match value {
+                turso_core::Value::Blob(blob) => {
+                    let blob_len = blob.len() as i32;
+                    unsafe { *data_len = blob_len };
+                    
+                    if blob.is_empty() {
+                        return Some(ptr::null_mut());
+                    }
+                    
+                    // Allocate memory for the blob data
+                    let layout = std::alloc::Layout::array::<u8>(blob.len()).ok()?;
+                    let ptr = unsafe { std::alloc::alloc(layout) };
+                    if ptr.is_null() {
+                        unsafe { *data_len = 0 };
+                        return None;
+                    }
+                    
+                    // Copy blob data to allocated memory
+                    unsafe {
+                        std::ptr::copy_nonoverlapping(blob.as_ptr(), ptr, blob.len());
+                    }
+                    
+                    Some(ptr)
+                }
+                _ => {
+                    unsafe { *data_len = 0 };
+                    None
+                }
             }
         } else {
+            unsafe { *data_len = 0 };
             None
         }
     });
 
-    result.unwrap_or(None).unwrap_or(-1)
+    result.unwrap_or(None).unwrap_or_else(|| {
+        unsafe { *data_len = 0; }
+        ptr::null_mut()
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn turso_free_blob(ptr: *mut u8, data_len: i32) {
+    if !ptr.is_null() && data_len > 0 {
+        unsafe {
+            let layout = std::alloc::Layout::array::<u8>(data_len as usize).unwrap();
+            std::alloc::dealloc(ptr, layout);
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn turso_statement_bind_null(
+    statement_ptr: *mut c_void,
+    param_index: i32,
+) -> TursoFFIResult {
+    if statement_ptr.is_null() || param_index < 1 {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
+        
+        match NonZero::new(param_index as usize) {
+            Some(index) => {
+                let mut stmt = statement_wrapper.statement.lock()
+                    .map_err(|e| Error::MutexError(e.to_string()))?;
+                stmt.bind_at(index, turso_core::Value::Null);
+                Ok(())
+            }
+            None => Err(Error::SqlExecutionFailure("Parameter index must be greater than 0".to_string())),
+        }
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in statement_bind_null".to_string()))))
+}
+
+// Transaction operations
+// Behavior used by begin calls that pass a negative behavior and by implicit transactions
+// such as transactional batch execution
+#[no_mangle]
+pub extern "C" fn turso_connection_set_default_transaction_behavior(
+    connection_ptr: *mut c_void,
+    behavior: i32, // 0 = Deferred, 1 = Immediate, 2 = Exclusive
+) -> TursoFFIResult {
+    if connection_ptr.is_null() {
+        return TursoFFIResult::misuse("Connection pointer is null");
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+
+        let behavior = TransactionBehavior::try_from(behavior)?;
+        let mut default_behavior = connection_wrapper.state.default_transaction_behavior.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+        *default_behavior = behavior;
+        Ok(())
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in set_default_transaction_behavior".to_string()))))
+}
+
+// Transactions do not nest: calling begin while one is open fails, since turso_core
+// does not support the savepoints nesting would need yet
+#[no_mangle]
+pub extern "C" fn turso_connection_begin_transaction(
+    connection_ptr: *mut c_void,
+    behavior: i32, // -1 = connection default, 0 = Deferred, 1 = Immediate, 2 = Exclusive
+) -> TursoFFIResult {
+    if connection_ptr.is_null() {
+        return TursoFFIResult::misuse("Connection pointer is null");
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+        
+        let behavior = connection_wrapper.state.transaction_behavior(behavior)?;
+
+        let conn = connection_wrapper.connection()?;
+
+        begin_transaction(&conn, behavior, &connection_wrapper.state)?;
+        Ok(())
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in begin_transaction".to_string()))))
+}
+
+#[no_mangle]
+pub extern "C" fn turso_connection_commit_transaction(connection_ptr: *mut c_void) -> TursoFFIResult {
+    if connection_ptr.is_null() {
+        return TursoFFIResult::misuse("Connection pointer is null");
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+        
+        let conn = connection_wrapper.connection()?;
+
+        commit_transaction(&conn, &connection_wrapper.state)
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in commit_transaction".to_string()))))
+}
+
+#[no_mangle]
+pub extern "C" fn turso_connection_rollback_transaction(connection_ptr: *mut c_void) -> TursoFFIResult {
+    if connection_ptr.is_null() {
+        return TursoFFIResult::misuse("Connection pointer is null");
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+        
+        let conn = connection_wrapper.connection()?;
+
+        rollback_transaction(&conn, &connection_wrapper.state)
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in rollback_transaction".to_string()))))
 }
 
+// Transaction handle operations
+// Begin a transaction owned by the returned handle. drop_behavior (0 = Rollback,
+// 1 = Commit, 2 = Ignore, 3 = Panic) is applied by turso_transaction_finalize if the
+// transaction was neither committed nor rolled back, so an abandoned handle never
+// leaves its connection inside a transaction unless Ignore was requested.
 #[no_mangle]
-pub extern "C" fn turso_statement_column_type(
-    statement_ptr: *mut c_void,
-    column_index: i32,
-) -> i32 {
-    if statement_ptr.is_null() || column_index < 0 {
-        return -1;
+pub extern "C" fn turso_transaction_begin(
+    connection_ptr: *mut c_void,
+    behavior: i32, // -1 = connection default, 0 = Deferred, 1 = Immediate, 2 = Exclusive
+    drop_behavior: i32,
+) -> *mut c_void {
+    if connection_ptr.is_null() {
+        set_last_error(TursoErrorCode::Misuse, "Connection pointer is null");
+        return ptr::null_mut();
     }
 
     let result = std::panic::catch_unwind(|| {
-        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
-        let stmt = statement_wrapper.statement.lock().ok()?;
-        
-        if let Some(row) = stmt.row() {
-            let value = row.get_value(column_index as usize);
-            match value {
-                Value::Null => Some(0),      // NULL
-                Value::Integer(_) => Some(1), // INTEGER
-                Value::Float(_) => Some(2),   // REAL/FLOAT
-                Value::Text(_) => Some(3),    // TEXT
-                Value::Blob(_) => Some(4),    // BLOB
-            }
-        } else {
-            None
-        }
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+
+        let behavior = connection_wrapper.state.transaction_behavior(behavior)?;
+        let drop_behavior = DropBehavior::try_from(drop_behavior)?;
+
+        let transaction = Transaction::begin(
+            connection_wrapper.connection.clone(),
+            connection_wrapper.state.clone(),
+            behavior,
+            drop_behavior,
+        )?;
+
+        Ok(TransactionWrapper {
+            transaction: Mutex::new(transaction),
+        })
     });
 
-    result.unwrap_or(None).unwrap_or(-1)
+    match record_outcome(result, "Panic in transaction_begin") {
+        Some(wrapper) => {
+            let boxed = Box::new(wrapper);
+            Box::into_raw(boxed) as *mut c_void
+        }
+        None => ptr::null_mut(),
+    }
 }
 
 #[no_mangle]
-pub extern "C" fn turso_rows_column_int64(
-    rows_ptr: *mut c_void,
-    column_index: i32,
-) -> i64 {
-    if rows_ptr.is_null() || column_index < 0 {
-        return 0;
+pub extern "C" fn turso_transaction_commit(transaction_ptr: *mut c_void) -> TursoFFIResult {
+    if transaction_ptr.is_null() {
+        return TursoFFIResult::misuse("Transaction pointer is null");
     }
 
     let result = std::panic::catch_unwind(|| {
-        let rows_wrapper = unsafe { &*(rows_ptr as *const RowsWrapper) };
-        let stmt = rows_wrapper.inner.lock().ok()?;
-        
-        if let Some(row) = stmt.row() {
-            let value = row.get_value(column_index as usize);
-            match value {
-                Value::Integer(i) => Some(*i),
-                _ => Some(0),
-            }
-        } else {
-            Some(0)
-        }
+        let transaction_wrapper = unsafe { &*(transaction_ptr as *const TransactionWrapper) };
+        let mut transaction = transaction_wrapper.transaction.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+        transaction.commit()
     });
 
-    result.unwrap_or(None).unwrap_or(0)
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in transaction_commit".to_string()))))
 }
 
 #[no_mangle]
-pub extern "C" fn turso_statement_column_int64(
-    statement_ptr: *mut c_void,
-    column_index: i32,
-) -> i64 {
-    if statement_ptr.is_null() || column_index < 0 {
-        return 0;
+pub extern "C" fn turso_transaction_rollback(transaction_ptr: *mut c_void) -> TursoFFIResult {
+    if transaction_ptr.is_null() {
+        return TursoFFIResult::misuse("Transaction pointer is null");
     }
 
     let result = std::panic::catch_unwind(|| {
-        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
-        let stmt = statement_wrapper.statement.lock().ok()?;
-        
-        if let Some(row) = stmt.row() {
-            let value = row.get_value(column_index as usize);
-            match value {
-                Value::Integer(i) => Some(*i),
-                _ => Some(0),
-            }
-        } else {
-            Some(0)
-        }
+        let transaction_wrapper = unsafe { &*(transaction_ptr as *const TransactionWrapper) };
+        let mut transaction = transaction_wrapper.transaction.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+        transaction.rollback()
     });
 
-    result.unwrap_or(None).unwrap_or(0)
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in transaction_rollback".to_string()))))
 }
 
+// Free the handle, first applying its drop behavior if it is still open
 #[no_mangle]
-pub extern "C" fn turso_rows_column_double(
-    rows_ptr: *mut c_void,
-    column_index: i32,
-) -> f64 {
-    if rows_ptr.is_null() || column_index < 0 {
-        return 0.0;
+pub extern "C" fn turso_transaction_finalize(transaction_ptr: *mut c_void) -> TursoFFIResult {
+    if transaction_ptr.is_null() {
+        return TursoFFIResult::misuse("Transaction pointer is null");
     }
 
     let result = std::panic::catch_unwind(|| {
-        let rows_wrapper = unsafe { &*(rows_ptr as *const RowsWrapper) };
-        let stmt = rows_wrapper.inner.lock().ok()?;
-        
-        if let Some(row) = stmt.row() {
-            let value = row.get_value(column_index as usize);
-            match value {
-                Value::Float(f) => Some(*f),
-                Value::Integer(i) => Some(*i as f64),
-                _ => Some(0.0),
-            }
-        } else {
-            Some(0.0)
-        }
+        let transaction_wrapper = unsafe { Box::from_raw(transaction_ptr as *mut TransactionWrapper) };
+        let mut transaction = transaction_wrapper.transaction.into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        transaction.finish_with_drop_behavior()
     });
 
-    result.unwrap_or(None).unwrap_or(0.0)
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in transaction_finalize".to_string()))))
 }
 
 #[no_mangle]
-pub extern "C" fn turso_statement_column_double(
-    statement_ptr: *mut c_void,
-    column_index: i32,
-) -> f64 {
-    if statement_ptr.is_null() || column_index < 0 {
-        return 0.0;
+pub extern "C" fn turso_connection_is_autocommit(
+    connection_ptr: *mut c_void,
+    result: *mut bool,
+) -> TursoFFIResult {
+    if connection_ptr.is_null() || result.is_null() {
+        return TursoFFIResult::misuse("Invalid parameters");
     }
 
-    let result = std::panic::catch_unwind(|| {
-        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
-        let stmt = statement_wrapper.statement.lock().ok()?;
+    let query_result = std::panic::catch_unwind(|| {
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
         
-        if let Some(row) = stmt.row() {
-            let value = row.get_value(column_index as usize);
-            match value {
-                Value::Float(f) => Some(*f),
-                Value::Integer(i) => Some(*i as f64),
-                _ => Some(0.0),
-            }
-        } else {
-            Some(0.0)
-        }
+        let conn = connection_wrapper.connection.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+
+        let autocommit = conn.get_auto_commit();
+        unsafe { *result = autocommit };
+        Ok(())
     });
 
-    result.unwrap_or(None).unwrap_or(0.0)
+    TursoFFIResult::from_result(query_result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in is_autocommit".to_string()))))
+}
+
+// Change tracking
+fn read_connection_counter(
+    connection_ptr: *mut c_void,
+    result: *mut i64,
+    read: fn(&Connection) -> i64,
+    panic_message: &str,
+) -> TursoFFIResult {
+    if connection_ptr.is_null() || result.is_null() {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    let query_result = std::panic::catch_unwind(|| {
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+
+        let conn = connection_wrapper.connection.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+
+        unsafe { *result = read(&conn) };
+        Ok(())
+    });
+
+    TursoFFIResult::from_result(query_result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure(panic_message.to_string()))))
 }
 
+// Rowid of the most recent successful INSERT on the connection
 #[no_mangle]
-pub extern "C" fn turso_rows_column_text(
-    rows_ptr: *mut c_void,
-    column_index: i32,
-) -> *mut c_char {
-    if rows_ptr.is_null() || column_index < 0 {
-        return ptr::null_mut();
+pub extern "C" fn turso_connection_last_insert_rowid(
+    connection_ptr: *mut c_void,
+    result: *mut i64,
+) -> TursoFFIResult {
+    read_connection_counter(connection_ptr, result, Connection::last_insert_rowid, "Panic in last_insert_rowid")
+}
+
+// Rows changed by the most recently completed statement
+#[no_mangle]
+pub extern "C" fn turso_connection_changes(
+    connection_ptr: *mut c_void,
+    result: *mut i64,
+) -> TursoFFIResult {
+    read_connection_counter(connection_ptr, result, Connection::changes, "Panic in connection_changes")
+}
+
+// Rows changed since the connection was opened
+#[no_mangle]
+pub extern "C" fn turso_connection_total_changes(
+    connection_ptr: *mut c_void,
+    result: *mut i64,
+) -> TursoFFIResult {
+    read_connection_counter(connection_ptr, result, Connection::total_changes, "Panic in connection_total_changes")
+}
+
+// Interrupt every statement running on the connection, including one that has returned
+// a row and has not yet been stepped to completion or reset, as sqlite3_interrupt does.
+// Safe to call from another thread while a step is in progress: it only sets a flag
+// checked by the step loops and never takes the connection lock. A no-op when nothing
+// is running.
+#[no_mangle]
+pub extern "C" fn turso_connection_interrupt(connection_ptr: *mut c_void) -> TursoFFIResult {
+    if connection_ptr.is_null() {
+        return TursoFFIResult::misuse("Connection pointer is null");
     }
 
     let result = std::panic::catch_unwind(|| {
-        let rows_wrapper = unsafe { &*(rows_ptr as *const RowsWrapper) };
-        let stmt = rows_wrapper.inner.lock().ok()?;
-        
-        if let Some(row) = stmt.row() {
-            let value = row.get_value(column_index as usize);
-            let text = match value {
-                Value::Text(s) => s.to_string(),
-                Value::Integer(i) => i.to_string(),
-                Value::Float(f) => f.to_string(),
-                _ => return None,
-            };
-            CString::new(text).ok().map(|c| c.into_raw())
-        } else {
-            None
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+        let state = &connection_wrapper.state;
+        if state.running_statements.load(Ordering::Acquire) > 0 {
+            state.interrupt_requested.store(true, Ordering::Release);
         }
+        Ok(())
     });
 
-    result.unwrap_or(None).unwrap_or(ptr::null_mut())
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in connection_interrupt".to_string()))))
 }
 
+// Busy handling
+// Retry a locked database for up to timeout_ms milliseconds; zero or negative disables
+// retrying. Replaces any busy handler, matching sqlite3_busy_timeout.
 #[no_mangle]
-pub extern "C" fn turso_statement_column_text(
-    statement_ptr: *mut c_void,
-    column_index: i32,
-) -> *mut c_char {
-    if statement_ptr.is_null() || column_index < 0 {
-        return ptr::null_mut();
+pub extern "C" fn turso_connection_set_busy_timeout(
+    connection_ptr: *mut c_void,
+    timeout_ms: i32,
+) -> TursoFFIResult {
+    if connection_ptr.is_null() {
+        return TursoFFIResult::misuse("Connection pointer is null");
     }
 
     let result = std::panic::catch_unwind(|| {
-        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
-        let stmt = statement_wrapper.statement.lock().ok()?;
-        
-        if let Some(row) = stmt.row() {
-            let value = row.get_value(column_index as usize);
-            let text = match value {
-                Value::Text(s) => s.to_string(),
-                Value::Integer(i) => i.to_string(),
-                Value::Float(f) => f.to_string(),
-                _ => return None,
-            };
-            CString::new(text).ok().map(|c| c.into_raw())
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+
+        let policy = if timeout_ms > 0 {
+            BusyPolicy::Timeout(std::time::Duration::from_millis(timeout_ms as u64))
         } else {
-            None
-        }
+            BusyPolicy::Fail
+        };
+
+        let mut busy_policy = connection_wrapper.state.busy_policy.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+        *busy_policy = policy;
+        Ok(())
     });
 
-    result.unwrap_or(None).unwrap_or(ptr::null_mut())
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in set_busy_timeout".to_string()))))
 }
 
+// Install a host busy handler, or clear it by passing null. Replaces any busy timeout.
 #[no_mangle]
-pub extern "C" fn turso_rows_column_is_null(
-    rows_ptr: *mut c_void,
-    column_index: i32,
-) -> bool {
-    if rows_ptr.is_null() || column_index < 0 {
-        return true;
+pub extern "C" fn turso_connection_set_busy_handler(
+    connection_ptr: *mut c_void,
+    handler: Option<extern "C" fn(user_data: *mut c_void, attempts: i32) -> i32>,
+    user_data: *mut c_void,
+) -> TursoFFIResult {
+    if connection_ptr.is_null() {
+        return TursoFFIResult::misuse("Connection pointer is null");
     }
 
     let result = std::panic::catch_unwind(|| {
-        let rows_wrapper = unsafe { &*(rows_ptr as *const RowsWrapper) };
-        let stmt = rows_wrapper.inner.lock().ok()?;
-        
-        if let Some(row) = stmt.row() {
-            let value = row.get_value(column_index as usize);
-            Some(matches!(value, Value::Null))
-        } else {
-            Some(true)
-        }
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+
+        let policy = match handler {
+            Some(callback) => BusyPolicy::Handler { callback, user_data: HostPtr(user_data) },
+            None => BusyPolicy::Fail,
+        };
+
+        let mut busy_policy = connection_wrapper.state.busy_policy.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+        *busy_policy = policy;
+        Ok(())
     });
 
-    result.unwrap_or(None).unwrap_or(true)
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in set_busy_handler".to_string()))))
 }
 
-#[no_mangle]
-pub extern "C" fn turso_statement_column_is_null(
-    statement_ptr: *mut c_void,
-    column_index: i32,
-) -> bool {
-    if statement_ptr.is_null() || column_index < 0 {
-        return true;
+// WAL operations
+// Checkpoint modes as in sqlite3_wal_checkpoint_v2. Passive copies what it can without
+// waiting, Full waits for writers, Restart also waits for readers so the WAL starts over,
+// and Truncate additionally truncates the WAL file.
+fn checkpoint_mode(mode: i32) -> Result<turso_core::CheckpointMode> {
+    match mode {
+        0 => Ok(turso_core::CheckpointMode::Passive),
+        1 => Ok(turso_core::CheckpointMode::Full),
+        2 => Ok(turso_core::CheckpointMode::Restart),
+        3 => Ok(turso_core::CheckpointMode::Truncate),
+        _ => Err(Error::misuse("Invalid checkpoint mode")),
+    }
+}
+
+// Busy stays retryable; anything else from the WAL is reported as a WAL error
+fn wal_error(err: turso_core::LimboError) -> Error {
+    match err {
+        turso_core::LimboError::Busy => Error::busy(),
+        err => Error::WalOperationError(err.to_string()),
+    }
+}
+
+// Checkpoint the WAL into the database file. wal_frames receives the number of frames
+// in the WAL and checkpointed_frames the number copied into the database; either may
+// be null. A checkpoint blocked by other connections is retried according to the busy
+// timeout or handler and then reported as Busy, any other failure as a WAL error.
+#[no_mangle]
+pub extern "C" fn turso_connection_checkpoint(
+    connection_ptr: *mut c_void,
+    mode: i32, // 0 = Passive, 1 = Full, 2 = Restart, 3 = Truncate
+    wal_frames: *mut i64,
+    checkpointed_frames: *mut i64,
+) -> TursoFFIResult {
+    if connection_ptr.is_null() {
+        return TursoFFIResult::misuse("Connection pointer is null");
     }
 
     let result = std::panic::catch_unwind(|| {
-        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
-        let stmt = statement_wrapper.statement.lock().ok()?;
-        
-        if let Some(row) = stmt.row() {
-            let value = row.get_value(column_index as usize);
-            Some(matches!(value, Value::Null))
-        } else {
-            Some(true)
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+
+        let mode = checkpoint_mode(mode)?;
+        let conn = connection_wrapper.connection()?;
+
+        let state = &connection_wrapper.state;
+        let mut busy_retry = state.busy_retry();
+        let checkpoint = loop {
+            match conn.checkpoint(mode) {
+                Ok(checkpoint) => break checkpoint,
+                Err(turso_core::LimboError::Busy) => match busy_retry.retry_after() {
+                    Some(delay) => state.wait_for_retry(delay),
+                    None => return Err(wal_error(turso_core::LimboError::Busy)),
+                },
+                Err(err) => return Err(wal_error(err)),
+            }
+        };
+
+        if !wal_frames.is_null() {
+            unsafe { *wal_frames = checkpoint.num_wal_frames as i64; }
         }
+        if !checkpointed_frames.is_null() {
+            unsafe { *checkpointed_frames = checkpoint.num_checkpointed_frames as i64; }
+        }
+        Ok(())
     });
 
-    result.unwrap_or(None).unwrap_or(true)
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in connection_checkpoint".to_string()))))
 }
 
+// WAL frame access for replication. A frame is the 24-byte frame header followed by one
+// page, so buffers must hold page_size + 24 bytes. A primary reads frames 1 through
+// turso_connection_wal_frame_count and ships them; a replica applies them in order
+// between turso_connection_wal_insert_begin and turso_connection_wal_insert_end.
 #[no_mangle]
-pub extern "C" fn turso_statement_finalize(statement_ptr: *mut c_void) -> TursoFFIResult {
-    if statement_ptr.is_null() {
-        return TursoFFIResult::error("Statement pointer is null");
+pub extern "C" fn turso_connection_wal_frame_count(
+    connection_ptr: *mut c_void,
+    result: *mut u64,
+) -> TursoFFIResult {
+    if connection_ptr.is_null() || result.is_null() {
+        return TursoFFIResult::misuse("Invalid parameters");
     }
 
-    let result = std::panic::catch_unwind(|| {
-        unsafe {
-            let _statement = Box::from_raw(statement_ptr as *mut StatementWrapper);
-            // Statement will be dropped automatically
-        }
+    let query_result = std::panic::catch_unwind(|| {
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+
+        let conn = connection_wrapper.connection.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+        let frame_count = conn.wal_frame_count().map_err(wal_error)?;
+        unsafe { *result = frame_count };
         Ok(())
     });
 
-    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in statement_finalize".to_string()))))
+    TursoFFIResult::from_result(query_result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in wal_frame_count".to_string()))))
 }
 
-// Parameter binding operations
+// Copy frame frame_no (1-based) into buf, which must be exactly one frame long
 #[no_mangle]
-pub extern "C" fn turso_statement_bind_int64(
-    statement_ptr: *mut c_void,
-    param_index: i32,
-    value: i64,
+pub extern "C" fn turso_connection_wal_get_frame(
+    connection_ptr: *mut c_void,
+    frame_no: u64,
+    buf: *mut u8,
+    buf_len: u32,
 ) -> TursoFFIResult {
-    if statement_ptr.is_null() || param_index < 1 {
-        return TursoFFIResult::error("Invalid parameters");
+    if connection_ptr.is_null() || buf.is_null() || frame_no == 0 {
+        return TursoFFIResult::misuse("Invalid parameters");
     }
 
     let result = std::panic::catch_unwind(|| {
-        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
-        
-        match NonZero::new(param_index as usize) {
-            Some(index) => {
-                let mut stmt = statement_wrapper.statement.lock()
-                    .map_err(|e| Error::MutexError(e.to_string()))?;
-                stmt.bind_at(index, turso_core::Value::Integer(value));
-                Ok(())
-            }
-            None => Err(Error::SqlExecutionFailure("Parameter index must be greater than 0".to_string())),
-        }
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+        let frame = unsafe { std::slice::from_raw_parts_mut(buf, buf_len as usize) };
+
+        let conn = connection_wrapper.connection.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+        conn.wal_get_frame(frame_no, frame).map_err(wal_error)?;
+        Ok(())
     });
 
-    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in statement_bind_int64".to_string()))))
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in wal_get_frame".to_string()))))
 }
 
+// Start applying frames from another database. Holds the write lock until
+// turso_connection_wal_insert_end.
 #[no_mangle]
-pub extern "C" fn turso_statement_bind_double(
-    statement_ptr: *mut c_void,
-    param_index: i32,
-    value: f64,
-) -> TursoFFIResult {
-    if statement_ptr.is_null() || param_index < 1 {
-        return TursoFFIResult::error("Invalid parameters");
+pub extern "C" fn turso_connection_wal_insert_begin(connection_ptr: *mut c_void) -> TursoFFIResult {
+    if connection_ptr.is_null() {
+        return TursoFFIResult::misuse("Connection pointer is null");
     }
 
     let result = std::panic::catch_unwind(|| {
-        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
-        
-        match NonZero::new(param_index as usize) {
-            Some(index) => {
-                let mut stmt = statement_wrapper.statement.lock()
-                    .map_err(|e| Error::MutexError(e.to_string()))?;
-                stmt.bind_at(index, turso_core::Value::Float(value));
-                Ok(())
-            }
-            None => Err(Error::SqlExecutionFailure("Parameter index must be greater than 0".to_string())),
-        }
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+
+        let conn = connection_wrapper.connection.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+        conn.wal_insert_begin().map_err(wal_error)
     });
 
-    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in statement_bind_double".to_string()))))
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in wal_insert_begin".to_string()))))
 }
 
+// Append a frame read with turso_connection_wal_get_frame as frame frame_no
 #[no_mangle]
-pub extern "C" fn turso_statement_bind_text(
-    statement_ptr: *mut c_void,
-    param_index: i32,
-    value: *const c_char,
+pub extern "C" fn turso_connection_wal_insert_frame(
+    connection_ptr: *mut c_void,
+    frame_no: u64,
+    data: *const u8,
+    data_len: u32,
 ) -> TursoFFIResult {
-    if statement_ptr.is_null() || param_index < 1 || value.is_null() {
-        return TursoFFIResult::error("Invalid parameters");
+    if connection_ptr.is_null() || data.is_null() || frame_no == 0 {
+        return TursoFFIResult::misuse("Invalid parameters");
     }
 
     let result = std::panic::catch_unwind(|| {
-        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
-        
-        let value_str = unsafe {
-            match CStr::from_ptr(value).to_str() {
-                Ok(s) => s,
-                Err(_) => return Err(Error::SqlExecutionFailure("Invalid UTF-8 string".to_string())),
-            }
-        };
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+        let frame = unsafe { std::slice::from_raw_parts(data, data_len as usize) };
 
-        match NonZero::new(param_index as usize) {
-            Some(index) => {
-                let mut stmt = statement_wrapper.statement.lock()
-                    .map_err(|e| Error::MutexError(e.to_string()))?;
-                stmt.bind_at(index, turso_core::Value::from_text(value_str));
-                Ok(())
-            }
-            None => Err(Error::SqlExecutionFailure("Parameter index must be greater than 0".to_string())),
-        }
+        let conn = connection_wrapper.connection.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+        conn.wal_insert_frame(frame_no, frame).map_err(wal_error)?;
+        Ok(())
     });
 
-    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in statement_bind_text".to_string()))))
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in wal_insert_frame".to_string()))))
 }
 
-
 #[no_mangle]
-pub extern "C" fn turso_statement_reset(statement_ptr: *mut c_void) -> TursoFFIResult {
-    if statement_ptr.is_null() {
-        return TursoFFIResult::error("Statement pointer is null");
+pub extern "C" fn turso_connection_wal_insert_end(connection_ptr: *mut c_void) -> TursoFFIResult {
+    if connection_ptr.is_null() {
+        return TursoFFIResult::misuse("Connection pointer is null");
     }
 
     let result = std::panic::catch_unwind(|| {
-        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
-        let mut stmt = statement_wrapper.statement.lock()
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+
+        let conn = connection_wrapper.connection.lock()
             .map_err(|e| Error::MutexError(e.to_string()))?;
-        stmt.reset();
-        Ok(())
+        conn.wal_insert_end().map_err(wal_error)
     });
 
-    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in statement_reset".to_string()))))
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in wal_insert_end".to_string()))))
 }
 
-// Blob binding operations
+// Start a background task that checkpoints the database whenever the WAL passes the
+// frame threshold or age in options, replacing any task already running. It runs on a
+// small tokio runtime shared by all databases, with one connection of its own. Busy
+// checkpoints are retried on the next poll; other failures are passed to error_callback,
+// if given, on a maintenance thread, so user_data must stay usable from there until the
+// task is stopped. The callback may stop or restart the task, which then doesn't wait
+// for the callback to return.
 #[no_mangle]
-pub extern "C" fn turso_statement_bind_blob(
-    statement_ptr: *mut c_void,
-    param_index: i32,
-    data: *const u8,
-    data_len: i32,
+pub extern "C" fn turso_database_start_auto_checkpoint(
+    database_ptr: *mut c_void,
+    options: *const TursoAutoCheckpointOptions,
+    error_callback: Option<extern "C" fn(user_data: *mut c_void, code: TursoErrorCode, message: *const c_char)>,
+    user_data: *mut c_void,
 ) -> TursoFFIResult {
-    if statement_ptr.is_null() || param_index < 1 || data_len < 0 {
-        return TursoFFIResult::error("Invalid parameters");
-    }
-
-    // Allow null data pointer only if data_len is 0 (empty blob)
-    if data.is_null() && data_len > 0 {
-        return TursoFFIResult::error("Invalid parameters");
+    if database_ptr.is_null() || options.is_null() {
+        return TursoFFIResult::misuse("Invalid parameters");
     }
 
     let result = std::panic::catch_unwind(|| {
-        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
-        
-        let blob_data = if data_len == 0 {
-            Vec::new() // Empty blob
-        } else {
-            unsafe {
-                std::slice::from_raw_parts(data, data_len as usize).to_vec()
-            }
-        };
+        let database = unsafe { &*(database_ptr as *const DatabaseWrapper) };
+        let options = unsafe { &*options };
 
-        match NonZero::new(param_index as usize) {
-            Some(index) => {
-                let mut stmt = statement_wrapper.statement.lock()
-                    .map_err(|e| Error::MutexError(e.to_string()))?;
-                stmt.bind_at(index, turso_core::Value::from_blob(blob_data));
-                Ok(())
-            }
-            None => Err(Error::SqlExecutionFailure("Parameter index must be greater than 0".to_string())),
+        if options.wal_frame_threshold == 0 && options.max_wal_age_ms == 0 {
+            return Err(Error::SqlExecutionFailure("A WAL frame threshold or maximum age is required".to_string()));
         }
+        let poll_interval_ms = if options.poll_interval_ms == 0 { 1000 } else { options.poll_interval_ms };
+        let config = AutoCheckpointConfig {
+            frame_threshold: options.wal_frame_threshold,
+            max_age: (options.max_wal_age_ms > 0).then(|| std::time::Duration::from_millis(options.max_wal_age_ms)),
+            poll_interval: std::time::Duration::from_millis(poll_interval_ms),
+            mode: checkpoint_mode(options.mode)?,
+        };
+
+        // Stop the previous task first so its callback is not called after this returns.
+        // Tasks are dropped outside the lock, since dropping one waits for its callback,
+        // which may itself stop or start a task.
+        let previous = database.auto_checkpoint.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?
+            .take();
+        drop(previous);
+
+        let started = AutoCheckpoint::start(database.database.clone(), config, error_callback, user_data)?;
+        // Another thread may have started a task meanwhile; this one replaces it
+        let replaced = database.auto_checkpoint.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?
+            .replace(started);
+        drop(replaced);
+        Ok(())
     });
 
-    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in statement_bind_blob".to_string()))))
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in start_auto_checkpoint".to_string()))))
 }
 
+// Stop the background checkpoint task, waiting for a checkpoint in progress. Does nothing
+// if none is running. Closing the database also stops it.
 #[no_mangle]
-pub extern "C" fn turso_rows_column_blob(
-    rows_ptr: *mut c_void,
-    column_index: i32,
-    data_len: *mut i32,
-) -> *mut u8 {
-    if rows_ptr.is_null() || column_index < 0 || data_len.is_null() {
-        return ptr::null_mut();
+pub extern "C" fn turso_database_stop_auto_checkpoint(database_ptr: *mut c_void) -> TursoFFIResult {
+    if database_ptr.is_null() {
+        return TursoFFIResult::misuse("Database pointer is null");
     }
 
     let result = std::panic::catch_unwind(|| {
-        let rows_wrapper = unsafe { &*(rows_ptr as *const RowsWrapper) };
-        let stmt = rows_wrapper.inner.lock().ok()?;
-        
-        if let Some(row) = stmt.row() {
-            let value = row.get_value(column_index as usize);
-            match value {
-                turso_core::Value::Blob(blob) => {
-                    let blob_len = blob.len() as i32;
-                    unsafe { *data_len = blob_len };
-                    
-                    if blob.is_empty() {
-                        return Some(ptr::null_mut());
-                    }
-                    
-                    // Allocate memory for the blob data
-                    let layout = std::alloc::Layout::array::<u8>(blob.len()).ok()?;
-                    let ptr = unsafe { std::alloc::alloc(layout) };
-                    if ptr.is_null() {
-                        unsafe { *data_len = 0 };
-                        return None;
-                    }
-                    
-                    // Copy blob data to allocated memory
-                    unsafe {
-                        std::ptr::copy_nonoverlapping(blob.as_ptr(), ptr, blob.len());
-                    }
-                    
-                    Some(ptr)
-                }
-                _ => {
-                    unsafe { *data_len = 0 };
-                    None
-                }
-            }
-        } else {
-            unsafe { *data_len = 0 };
-            None
-        }
+        let database = unsafe { &*(database_ptr as *const DatabaseWrapper) };
+        // Dropped outside the lock, see turso_database_start_auto_checkpoint
+        let stopped = database.auto_checkpoint.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?
+            .take();
+        drop(stopped);
+        Ok(())
     });
 
-    result.unwrap_or(None).unwrap_or_else(|| {
-        unsafe { *data_len = 0; }
-        ptr::null_mut()
-    })
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in stop_auto_checkpoint".to_string()))))
 }
 
+// Extension loading
+// Allow or forbid turso_connection_load_extension on this connection. Loading is off by
+// default because a loaded extension runs arbitrary native code. Extensions compiled into
+// turso_core are registered on every connection and are not affected by this switch.
 #[no_mangle]
-pub extern "C" fn turso_statement_column_blob(
-    statement_ptr: *mut c_void,
-    column_index: i32,
-    data_len: *mut i32,
-) -> *mut u8 {
-    if statement_ptr.is_null() || column_index < 0 || data_len.is_null() {
-        return ptr::null_mut();
+pub extern "C" fn turso_connection_enable_load_extension(
+    connection_ptr: *mut c_void,
+    enabled: bool,
+) -> TursoFFIResult {
+    if connection_ptr.is_null() {
+        return TursoFFIResult::misuse("Connection pointer is null");
     }
 
     let result = std::panic::catch_unwind(|| {
-        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
-        let stmt = statement_wrapper.statement.lock().ok()?;
-        
-        if let Some(row) = stmt.row() {
-            let value = row.get_value(column_index as usize);
-            match value {
-                turso_core::Value::Blob(blob) => {
-                    let blob_len = blob.len() as i32;
-                    unsafe { *data_len = blob_len };
-                    
-                    if blob.is_empty() {
-                        return Some(ptr::null_mut());
-                    }
-                    
-                    // Allocate memory for the blob data
-                    let layout = std::alloc::Layout::array::<u8>(blob.len()).ok()?;
-                    let ptr = unsafe { std::alloc::alloc(layout) };
-                    if ptr.is_null() {
-                        unsafe { *data_len = 0 };
-                        return None;
-                    }
-                    
-                    // Copy blob data to allocated memory
-                    unsafe {
-                        std::ptr::copy_nonoverlapping(blob.as_ptr(), ptr, blob.len());
-                    }
-                    
-                    Some(ptr)
-                }
-                _ => {
-                    unsafe { *data_len = 0 };
-                    None
-                }
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+        connection_wrapper.state.extension_loading_enabled.store(enabled, Ordering::Release);
+        Ok(())
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in enable_load_extension".to_string()))))
+}
+
+// Load a dynamic turso extension from path. entry_point may be null; turso_core always
+// calls the extension's register_extension function, so any other name is rejected.
+#[no_mangle]
+pub extern "C" fn turso_connection_load_extension(
+    connection_ptr: *mut c_void,
+    path: *const c_char,
+    entry_point: *const c_char,
+) -> TursoFFIResult {
+    if connection_ptr.is_null() || path.is_null() {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+
+        if !connection_wrapper.state.extension_loading_enabled.load(Ordering::Acquire) {
+            return Err(Error::CoreError {
+                code: TursoErrorCode::Error,
+                message: "Extension loading is not enabled for this connection".to_string(),
+            });
+        }
+
+        let path_str = unsafe {
+            match CStr::from_ptr(path).to_str() {
+                Ok(s) => s,
+                Err(_) => return Err(Error::SqlExecutionFailure("Invalid extension path".to_string())),
+            }
+        };
+
+        if !entry_point.is_null() {
+            let entry_point = unsafe { CStr::from_ptr(entry_point) };
+            if entry_point.to_bytes() != b"register_extension" {
+                return Err(Error::CoreError {
+                    code: TursoErrorCode::Error,
+                    message: format!(
+                        "Unsupported extension entry point {}, extensions must export register_extension",
+                        entry_point.to_string_lossy()
+                    ),
+                });
             }
-        } else {
-            unsafe { *data_len = 0 };
-            None
         }
+
+        let conn = connection_wrapper.connection.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+        load_extension(&conn, path_str)
     });
 
-    result.unwrap_or(None).unwrap_or_else(|| {
-        unsafe { *data_len = 0; }
-        ptr::null_mut()
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in load_extension".to_string()))))
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn load_extension(conn: &Arc<Connection>, path: &str) -> Result<()> {
+    conn.load_extension(path)?;
+    Ok(())
+}
+
+#[cfg(target_family = "wasm")]
+fn load_extension(_conn: &Arc<Connection>, _path: &str) -> Result<()> {
+    Err(Error::CoreError {
+        code: TursoErrorCode::Error,
+        message: "Loading extensions needs shared libraries, which this platform does not have".to_string(),
     })
 }
 
+// Transaction hooks. The commit hook runs before a transaction commits and can veto it
+// by returning non-zero, which rolls the transaction back and fails the commit with
+// ConstraintCommitHook; it is asked once per transaction, and a commit it approved can
+// still fail, e.g. with BUSY. The post-commit hook runs after COMMIT succeeded, so only
+// for commits that happened. The rollback hook runs after a transaction rolls back.
+// They fire for transactions ended through these bindings, for COMMIT and ROLLBACK
+// statements, and for writes made in autocommit mode, which get a transaction of their
+// own while any hook is installed. Pass null to remove a hook.
 #[no_mangle]
-pub extern "C" fn turso_free_blob(ptr: *mut u8, data_len: i32) {
-    if !ptr.is_null() && data_len > 0 {
-        unsafe {
-            let layout = std::alloc::Layout::array::<u8>(data_len as usize).unwrap();
-            std::alloc::dealloc(ptr, layout);
-        }
+pub extern "C" fn turso_connection_set_commit_hook(
+    connection_ptr: *mut c_void,
+    hook: Option<extern "C" fn(user_data: *mut c_void) -> i32>,
+    user_data: *mut c_void,
+) -> TursoFFIResult {
+    if connection_ptr.is_null() {
+        return TursoFFIResult::misuse("Connection pointer is null");
     }
+
+    let result = std::panic::catch_unwind(|| {
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+
+        let mut commit_hook = connection_wrapper.state.commit_hook.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+        *commit_hook = hook.map(|callback| Hook { callback, user_data: HostPtr(user_data) });
+        Ok(())
+    });
+
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in set_commit_hook".to_string()))))
 }
 
 #[no_mangle]
-pub extern "C" fn turso_statement_bind_null(
-    statement_ptr: *mut c_void,
-    param_index: i32,
+pub extern "C" fn turso_connection_set_post_commit_hook(
+    connection_ptr: *mut c_void,
+    hook: Option<extern "C" fn(user_data: *mut c_void)>,
+    user_data: *mut c_void,
 ) -> TursoFFIResult {
-    if statement_ptr.is_null() || param_index < 1 {
-        return TursoFFIResult::error("Invalid parameters");
+    if connection_ptr.is_null() {
+        return TursoFFIResult::misuse("Connection pointer is null");
     }
 
     let result = std::panic::catch_unwind(|| {
-        let statement_wrapper = unsafe { &*(statement_ptr as *const StatementWrapper) };
-        
-        match NonZero::new(param_index as usize) {
-            Some(index) => {
-                let mut stmt = statement_wrapper.statement.lock()
-                    .map_err(|e| Error::MutexError(e.to_string()))?;
-                stmt.bind_at(index, turso_core::Value::Null);
-                Ok(())
-            }
-            None => Err(Error::SqlExecutionFailure("Parameter index must be greater than 0".to_string())),
-        }
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
+
+        let mut post_commit_hook = connection_wrapper.state.post_commit_hook.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+        *post_commit_hook = hook.map(|callback| Hook { callback, user_data: HostPtr(user_data) });
+        Ok(())
     });
 
-    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in statement_bind_null".to_string()))))
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in set_post_commit_hook".to_string()))))
 }
 
-// Transaction operations
 #[no_mangle]
-pub extern "C" fn turso_connection_begin_transaction(
+pub extern "C" fn turso_connection_set_rollback_hook(
     connection_ptr: *mut c_void,
-    behavior: i32, // 0 = Deferred, 1 = Immediate, 2 = Exclusive
+    hook: Option<extern "C" fn(user_data: *mut c_void)>,
+    user_data: *mut c_void,
 ) -> TursoFFIResult {
     if connection_ptr.is_null() {
-        return TursoFFIResult::error("Connection pointer is null");
+        return TursoFFIResult::misuse("Connection pointer is null");
     }
 
     let result = std::panic::catch_unwind(|| {
-        let connection_wrapper = unsafe { &mut *(connection_ptr as *mut ConnectionWrapper) };
-        
-        let behavior = match behavior {
-            0 => TransactionBehavior::Deferred,
-            1 => TransactionBehavior::Immediate,
-            2 => TransactionBehavior::Exclusive,
-            _ => return Err(Error::SqlExecutionFailure("Invalid transaction behavior".to_string())),
-        };
-
-        connection_wrapper.transaction_behavior = behavior;
+        let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
 
-        let conn = connection_wrapper.connection.lock()
+        let mut rollback_hook = connection_wrapper.state.rollback_hook.lock()
             .map_err(|e| Error::MutexError(e.to_string()))?;
-
-        let sql = match behavior {
-            TransactionBehavior::Deferred => "BEGIN DEFERRED",
-            TransactionBehavior::Immediate => "BEGIN IMMEDIATE", 
-            TransactionBehavior::Exclusive => "BEGIN EXCLUSIVE",
-        };
-
-        let mut stmt = conn.prepare(sql)?;
-        
-        loop {
-            match stmt.step() {
-                Ok(turso_core::StepResult::Done) => return Ok(()),
-                Ok(turso_core::StepResult::IO) => {
-                    stmt.run_once()?;
-                }
-                Ok(turso_core::StepResult::Busy) => {
-                    return Err(Error::SqlExecutionFailure("database is locked".to_string()));
-                }
-                Ok(turso_core::StepResult::Interrupt) => {
-                    return Err(Error::SqlExecutionFailure("interrupted".to_string()));
-                }
-                Ok(turso_core::StepResult::Row) => {
-                    return Err(Error::SqlExecutionFailure("unexpected row during transaction begin".to_string()));
-                }
-                Err(err) => {
-                    return Err(err.into());
-                }
-            }
-        }
+        *rollback_hook = hook.map(|callback| Hook { callback, user_data: HostPtr(user_data) });
+        Ok(())
     });
 
-    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in begin_transaction".to_string()))))
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in set_rollback_hook".to_string()))))
 }
 
+// Application-defined functions
+// Register a scalar SQL function backed by a host callback. n_args is the exact number
+// of arguments, or -1 for any number. The callback runs on the thread stepping the
+// statement, in the middle of that step, so it must not step statements on this
+// connection. At most 64 distinct function names can be registered in the process;
+// connections registering the same name count once.
 #[no_mangle]
-pub extern "C" fn turso_connection_commit_transaction(connection_ptr: *mut c_void) -> TursoFFIResult {
-    if connection_ptr.is_null() {
-        return TursoFFIResult::error("Connection pointer is null");
+pub extern "C" fn turso_connection_create_scalar_function(
+    connection_ptr: *mut c_void,
+    name: *const c_char,
+    n_args: i32,
+    deterministic: bool,
+    callback: Option<extern "C" fn(user_data: *mut c_void, context: *mut c_void, argc: i32, argv: *const *const c_void)>,
+    user_data: *mut c_void,
+) -> TursoFFIResult {
+    if connection_ptr.is_null() || name.is_null() || n_args < -1 {
+        return TursoFFIResult::misuse("Invalid parameters");
     }
+    let Some(callback) = callback else {
+        return TursoFFIResult::misuse("Function callback is null");
+    };
+    // turso_core does not distinguish deterministic external functions yet
+    let _ = deterministic;
 
     let result = std::panic::catch_unwind(|| {
         let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
-        
-        let conn = connection_wrapper.connection.lock()
-            .map_err(|e| Error::MutexError(e.to_string()))?;
 
-        let mut stmt = conn.prepare("COMMIT")?;
-        
-        loop {
-            match stmt.step() {
-                Ok(turso_core::StepResult::Done) => return Ok(()),
-                Ok(turso_core::StepResult::IO) => {
-                    stmt.run_once()?;
-                }
-                Ok(turso_core::StepResult::Busy) => {
-                    return Err(Error::SqlExecutionFailure("database is locked".to_string()));
-                }
-                Ok(turso_core::StepResult::Interrupt) => {
-                    return Err(Error::SqlExecutionFailure("interrupted".to_string()));
-                }
-                Ok(turso_core::StepResult::Row) => {
-                    return Err(Error::SqlExecutionFailure("unexpected row during commit".to_string()));
-                }
-                Err(err) => {
-                    return Err(err.into());
-                }
+        let name_str = unsafe {
+            match CStr::from_ptr(name).to_str() {
+                Ok(s) => s,
+                Err(_) => return Err(Error::SqlExecutionFailure("Invalid function name".to_string())),
             }
-        }
+        };
+
+        let conn = connection_wrapper.connection.lock()
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+        let owner = Arc::as_ptr(&connection_wrapper.state) as usize;
+        functions::register_scalar_function(&conn, owner, name_str, n_args, callback, user_data)
     });
 
-    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in commit_transaction".to_string()))))
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in create_scalar_function".to_string()))))
 }
 
+// Register an aggregate SQL function. step runs for every row of a group and final
+// reports the group's result; per-group state lives in turso_aggregate_context. inverse
+// and value are the window function callbacks, which turso_core's function registry has
+// no way to call yet, so passing them fails instead of silently ignoring them. Counts
+// towards the same function name limit as scalar functions.
 #[no_mangle]
-pub extern "C" fn turso_connection_rollback_transaction(connection_ptr: *mut c_void) -> TursoFFIResult {
-    if connection_ptr.is_null() {
-        return TursoFFIResult::error("Connection pointer is null");
+pub extern "C" fn turso_connection_create_aggregate_function(
+    connection_ptr: *mut c_void,
+    name: *const c_char,
+    n_args: i32,
+    step: Option<extern "C" fn(user_data: *mut c_void, context: *mut c_void, argc: i32, argv: *const *const c_void)>,
+    final_callback: Option<extern "C" fn(user_data: *mut c_void, context: *mut c_void)>,
+    inverse: Option<extern "C" fn(user_data: *mut c_void, context: *mut c_void, argc: i32, argv: *const *const c_void)>,
+    value: Option<extern "C" fn(user_data: *mut c_void, context: *mut c_void)>,
+    user_data: *mut c_void,
+) -> TursoFFIResult {
+    if connection_ptr.is_null() || name.is_null() || n_args < -1 {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+    let (Some(step), Some(final_callback)) = (step, final_callback) else {
+        return TursoFFIResult::misuse("Aggregate step and final callbacks are required");
+    };
+    if inverse.is_some() || value.is_some() {
+        return TursoFFIResult::error(TursoErrorCode::Error, "Window functions are not supported");
     }
 
     let result = std::panic::catch_unwind(|| {
         let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
-        
+
+        let name_str = unsafe {
+            match CStr::from_ptr(name).to_str() {
+                Ok(s) => s,
+                Err(_) => return Err(Error::SqlExecutionFailure("Invalid function name".to_string())),
+            }
+        };
+
         let conn = connection_wrapper.connection.lock()
             .map_err(|e| Error::MutexError(e.to_string()))?;
+        let owner = Arc::as_ptr(&connection_wrapper.state) as usize;
+        functions::register_aggregate_function(&conn, owner, name_str, n_args, step, final_callback, user_data)
+    });
 
-        let mut stmt = conn.prepare("ROLLBACK")?;
-        
-        loop {
-            match stmt.step() {
-                Ok(turso_core::StepResult::Done) => return Ok(()),
-                Ok(turso_core::StepResult::IO) => {
-                    stmt.run_once()?;
-                }
-                Ok(turso_core::StepResult::Busy) => {
-                    return Err(Error::SqlExecutionFailure("database is locked".to_string()));
-                }
-                Ok(turso_core::StepResult::Interrupt) => {
-                    return Err(Error::SqlExecutionFailure("interrupted".to_string()));
-                }
-                Ok(turso_core::StepResult::Row) => {
-                    return Err(Error::SqlExecutionFailure("unexpected row during rollback".to_string()));
-                }
-                Err(err) => {
-                    return Err(err.into());
-                }
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in create_aggregate_function".to_string()))))
+}
+
+// Per-group storage for aggregate callbacks, like sqlite3_aggregate_context. The first
+// call in a group allocates n_bytes of zeroed memory, later calls return the same block,
+// which is freed after the group's final callback. Null outside aggregate callbacks.
+#[no_mangle]
+pub extern "C" fn turso_aggregate_context(context_ptr: *mut c_void, n_bytes: i32) -> *mut c_void {
+    if context_ptr.is_null() {
+        return ptr::null_mut();
+    }
+
+    let context = unsafe { &mut *(context_ptr as *mut FunctionContext) };
+    context.aggregate_storage(n_bytes.max(0) as usize)
+}
+
+// Function argument accessors, valid only inside the callback that received the value.
+// The type uses the same numbering as turso_statement_column_type.
+#[no_mangle]
+pub extern "C" fn turso_value_type(value_ptr: *const c_void) -> i32 {
+    if value_ptr.is_null() {
+        return -1;
+    }
+
+    let value = unsafe { &*(value_ptr as *const Value) };
+    match value {
+        Value::Null => 0,
+        Value::Integer(_) => 1,
+        Value::Float(_) => 2,
+        Value::Text(_) => 3,
+        Value::Blob(_) => 4,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn turso_value_int64(value_ptr: *const c_void) -> i64 {
+    if value_ptr.is_null() {
+        return 0;
+    }
+
+    let value = unsafe { &*(value_ptr as *const Value) };
+    match value {
+        Value::Integer(i) => *i,
+        Value::Float(f) => *f as i64,
+        _ => 0,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn turso_value_double(value_ptr: *const c_void) -> f64 {
+    if value_ptr.is_null() {
+        return 0.0;
+    }
+
+    let value = unsafe { &*(value_ptr as *const Value) };
+    match value {
+        Value::Float(f) => *f,
+        Value::Integer(i) => *i as f64,
+        _ => 0.0,
+    }
+}
+
+// Returns a string to be freed with turso_free_string, or null for NULL and blob values
+#[no_mangle]
+pub extern "C" fn turso_value_text(value_ptr: *const c_void) -> *mut c_char {
+    if value_ptr.is_null() {
+        return ptr::null_mut();
+    }
+
+    let value = unsafe { &*(value_ptr as *const Value) };
+    let text = match value {
+        Value::Text(s) => s.to_string(),
+        Value::Integer(i) => i.to_string(),
+        Value::Float(f) => f.to_string(),
+        _ => return ptr::null_mut(),
+    };
+    CString::new(text).map_or(ptr::null_mut(), |c| c.into_raw())
+}
+
+// Returns a copy to be freed with turso_free_blob, or null for empty and non-blob values
+#[no_mangle]
+pub extern "C" fn turso_value_blob(value_ptr: *const c_void, data_len: *mut i32) -> *mut u8 {
+    if value_ptr.is_null() || data_len.is_null() {
+        return ptr::null_mut();
+    }
+
+    unsafe { *data_len = 0 };
+    let value = unsafe { &*(value_ptr as *const Value) };
+    match value {
+        Value::Blob(blob) if !blob.is_empty() => {
+            let Ok(layout) = std::alloc::Layout::array::<u8>(blob.len()) else {
+                return ptr::null_mut();
+            };
+            let ptr = unsafe { std::alloc::alloc(layout) };
+            if ptr.is_null() {
+                return ptr::null_mut();
             }
+            unsafe {
+                std::ptr::copy_nonoverlapping(blob.as_ptr(), ptr, blob.len());
+                *data_len = blob.len() as i32;
+            }
+            ptr
         }
-    });
+        _ => ptr::null_mut(),
+    }
+}
 
-    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in rollback_transaction".to_string()))))
+// Function result setters, valid only inside the callback that received the context.
+// A function that sets no result returns NULL.
+fn set_function_result(context_ptr: *mut c_void, value: Value) -> TursoFFIResult {
+    if context_ptr.is_null() {
+        return TursoFFIResult::misuse("Function context is null");
+    }
+
+    let context = unsafe { &mut *(context_ptr as *mut FunctionContext) };
+    context.set_result(value);
+    TursoFFIResult::success()
 }
 
 #[no_mangle]
-pub extern "C" fn turso_connection_is_autocommit(
+pub extern "C" fn turso_result_null(context_ptr: *mut c_void) -> TursoFFIResult {
+    set_function_result(context_ptr, Value::Null)
+}
+
+#[no_mangle]
+pub extern "C" fn turso_result_int64(context_ptr: *mut c_void, value: i64) -> TursoFFIResult {
+    set_function_result(context_ptr, Value::Integer(value))
+}
+
+#[no_mangle]
+pub extern "C" fn turso_result_double(context_ptr: *mut c_void, value: f64) -> TursoFFIResult {
+    set_function_result(context_ptr, Value::Float(value))
+}
+
+#[no_mangle]
+pub extern "C" fn turso_result_text(context_ptr: *mut c_void, value: *const c_char) -> TursoFFIResult {
+    if value.is_null() {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    let value_str = unsafe {
+        match CStr::from_ptr(value).to_str() {
+            Ok(s) => s,
+            Err(_) => return TursoFFIResult::error(TursoErrorCode::Misuse, "Invalid text value"),
+        }
+    };
+    set_function_result(context_ptr, Value::from_text(value_str))
+}
+
+#[no_mangle]
+pub extern "C" fn turso_result_blob(context_ptr: *mut c_void, data: *const u8, data_len: i32) -> TursoFFIResult {
+    if data_len < 0 || (data.is_null() && data_len > 0) {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    let blob_data = if data_len == 0 {
+        Vec::new()
+    } else {
+        unsafe { std::slice::from_raw_parts(data, data_len as usize) }.to_vec()
+    };
+    set_function_result(context_ptr, Value::from_blob(blob_data))
+}
+
+// Make the function call fail with the given message
+#[no_mangle]
+pub extern "C" fn turso_result_error(context_ptr: *mut c_void, message: *const c_char) -> TursoFFIResult {
+    if context_ptr.is_null() || message.is_null() {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    let message = unsafe { CStr::from_ptr(message) }.to_string_lossy().into_owned();
+    let context = unsafe { &mut *(context_ptr as *mut FunctionContext) };
+    context.set_error(message);
+    TursoFFIResult::success()
+}
+
+// Virtual table modules
+// Register a read-only virtual table module, so CREATE VIRTUAL TABLE ... USING name(args)
+// is served by the host's callbacks. connect, open, close, filter, next, eof, column and
+// rowid are required. Callbacks run on the thread preparing or stepping the statement,
+// so like function callbacks they must not step statements on this connection. At most
+// 16 distinct module names can be registered in the process.
+#[no_mangle]
+pub extern "C" fn turso_connection_create_module(
     connection_ptr: *mut c_void,
-    result: *mut bool,
+    name: *const c_char,
+    module: *const TursoVTabModule,
+    user_data: *mut c_void,
 ) -> TursoFFIResult {
-    if connection_ptr.is_null() || result.is_null() {
-        return TursoFFIResult::error("Invalid parameters");
+    if connection_ptr.is_null() || name.is_null() || module.is_null() {
+        return TursoFFIResult::misuse("Invalid parameters");
     }
+    let module = unsafe { *module };
+    let (Some(connect), Some(open), Some(close), Some(filter), Some(next), Some(eof), Some(column), Some(rowid)) = (
+        module.connect, module.open, module.close, module.filter, module.next, module.eof, module.column, module.rowid,
+    ) else {
+        return TursoFFIResult::misuse("Module is missing a required callback");
+    };
+    let callbacks = vtab::ModuleCallbacks {
+        connect,
+        best_index: module.best_index,
+        disconnect: module.disconnect,
+        open,
+        close,
+        filter,
+        next,
+        eof,
+        column,
+        rowid,
+    };
 
-    let query_result = std::panic::catch_unwind(|| {
+    let result = std::panic::catch_unwind(|| {
         let connection_wrapper = unsafe { &*(connection_ptr as *const ConnectionWrapper) };
-        
+
+        let name_str = unsafe {
+            match CStr::from_ptr(name).to_str() {
+                Ok(s) => s,
+                Err(_) => return Err(Error::SqlExecutionFailure("Invalid module name".to_string())),
+            }
+        };
+
         let conn = connection_wrapper.connection.lock()
             .map_err(|e| Error::MutexError(e.to_string()))?;
+        let owner = Arc::as_ptr(&connection_wrapper.state) as usize;
+        vtab::register_module(&conn, owner, name_str, callbacks, user_data)
+    });
 
-        let autocommit = conn.get_auto_commit();
-        unsafe { *result = autocommit };
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in create_module".to_string()))))
+}
+
+// Declare the columns of the table being created, as a CREATE TABLE statement whose
+// table name is ignored. Only valid inside a module's connect callback.
+#[no_mangle]
+pub extern "C" fn turso_vtab_declare(context_ptr: *mut c_void, sql: *const c_char) -> TursoFFIResult {
+    if context_ptr.is_null() || sql.is_null() {
+        return TursoFFIResult::misuse("Invalid parameters");
+    }
+
+    let result = std::panic::catch_unwind(|| {
+        let context = unsafe { &mut *(context_ptr as *mut vtab::ModuleContext) };
+
+        let sql_str = unsafe {
+            match CStr::from_ptr(sql).to_str() {
+                Ok(s) => s,
+                Err(_) => return Err(Error::SqlExecutionFailure("Invalid SQL string".to_string())),
+            }
+        };
+
+        context.declare(sql_str.to_string());
         Ok(())
     });
 
-    TursoFFIResult::from_result(query_result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in is_autocommit".to_string()))))
+    TursoFFIResult::from_result(result.unwrap_or_else(|_| Err(Error::SqlExecutionFailure("Panic in vtab_declare".to_string()))))
+}
+
+// Last error accessors
+#[no_mangle]
+pub extern "C" fn turso_last_error_code() -> TursoErrorCode {
+    LAST_ERROR.with(|last_error| {
+        last_error.borrow().as_ref().map_or(TursoErrorCode::Ok, |(code, _)| code.primary())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn turso_last_error_extended_code() -> TursoErrorCode {
+    LAST_ERROR.with(|last_error| {
+        last_error.borrow().as_ref().map_or(TursoErrorCode::Ok, |(code, _)| *code)
+    })
+}
+
+// Returns a copy of the last error message on this thread (free with turso_free_string),
+// or null if the most recent call succeeded.
+#[no_mangle]
+pub extern "C" fn turso_last_error_message() -> *mut c_char {
+    LAST_ERROR.with(|last_error| {
+        last_error
+            .borrow()
+            .as_ref()
+            .and_then(|(_, message)| CString::new(message.as_str()).ok())
+            .map_or(ptr::null_mut(), |c| c.into_raw())
+    })
 }
 
 // Memory management
@@ -1383,4 +3464,90 @@ pub extern "C" fn turso_free_error_message(result: *mut TursoFFIResult) {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use turso_core::LimboError;
+
+    const EXTENDED_CODES: [TursoErrorCode; 9] = [
+        TursoErrorCode::IoErrRead,
+        TursoErrorCode::IoErrWrite,
+        TursoErrorCode::IoErrLock,
+        TursoErrorCode::ConstraintCheck,
+        TursoErrorCode::ConstraintForeignKey,
+        TursoErrorCode::ConstraintNotNull,
+        TursoErrorCode::ConstraintPrimaryKey,
+        TursoErrorCode::ConstraintUnique,
+        TursoErrorCode::ConstraintCommitHook,
+    ];
+
+    #[test]
+    fn extended_codes_carry_their_primary_code_in_the_low_byte() {
+        for code in EXTENDED_CODES {
+            let primary = code.primary();
+            assert_ne!(primary, code);
+            assert_eq!(code as i32 & 0xff, primary as i32, "{code:?}");
+            assert_eq!(primary.primary(), primary);
+        }
+        assert_eq!(TursoErrorCode::ConstraintUnique.primary(), TursoErrorCode::Constraint);
+        assert_eq!(TursoErrorCode::IoErrLock.primary(), TursoErrorCode::IoErr);
+        assert_eq!(TursoErrorCode::Busy.primary(), TursoErrorCode::Busy);
+    }
+
+    #[test]
+    fn constraint_messages_map_to_extended_codes() {
+        let cases = [
+            ("UNIQUE constraint failed: t.a", TursoErrorCode::ConstraintUnique),
+            ("PRIMARY KEY constraint failed: t.id", TursoErrorCode::ConstraintPrimaryKey),
+            ("NOT NULL constraint failed: t.b", TursoErrorCode::ConstraintNotNull),
+            ("FOREIGN KEY constraint failed", TursoErrorCode::ConstraintForeignKey),
+            ("CHECK constraint failed: positive", TursoErrorCode::ConstraintCheck),
+            ("constraint failed", TursoErrorCode::Constraint),
+        ];
+        for (message, code) in cases {
+            assert_eq!(TursoErrorCode::from_constraint_message(message), code, "{message}");
+        }
+    }
+
+    #[test]
+    fn limbo_errors_map_to_result_codes() {
+        let cases = [
+            (LimboError::Constraint("UNIQUE constraint failed: t.a".to_string()), TursoErrorCode::ConstraintUnique),
+            (LimboError::Busy, TursoErrorCode::Busy),
+            (LimboError::LockingError("locked".to_string()), TursoErrorCode::IoErrLock),
+            (LimboError::ReadOnly, TursoErrorCode::ReadOnly),
+            (LimboError::Interrupt, TursoErrorCode::Interrupt),
+            (LimboError::NotADB, TursoErrorCode::NotADb),
+            (LimboError::IOError(std::io::ErrorKind::NotFound.into()), TursoErrorCode::NotFound),
+            (LimboError::IOError(std::io::ErrorKind::UnexpectedEof.into()), TursoErrorCode::IoErrRead),
+            (LimboError::IOError(std::io::ErrorKind::Other.into()), TursoErrorCode::IoErr),
+            (LimboError::InvalidArgument("bad".to_string()), TursoErrorCode::Misuse),
+            (LimboError::ParseError("near x".to_string()), TursoErrorCode::Error),
+        ];
+        for (err, code) in cases {
+            assert_eq!(Error::from(err).code(), code);
+        }
+    }
+
+    #[test]
+    fn error_results_report_primary_and_extended_codes() {
+        let mut result = TursoFFIResult::from_result(Err(Error::CoreError {
+            code: TursoErrorCode::ConstraintNotNull,
+            message: "NOT NULL constraint failed: t.b".to_string(),
+        }));
+
+        assert!(!result.success);
+        assert_eq!(result.error_code, TursoErrorCode::Constraint);
+        assert_eq!(result.extended_error_code, TursoErrorCode::ConstraintNotNull);
+        assert_eq!(turso_last_error_code(), TursoErrorCode::Constraint);
+        assert_eq!(turso_last_error_extended_code(), TursoErrorCode::ConstraintNotNull);
+        turso_free_error_message(&mut result);
+
+        let result = TursoFFIResult::from_result(Ok(()));
+        assert_eq!(result.error_code, TursoErrorCode::Ok);
+        assert_eq!(result.extended_error_code, TursoErrorCode::Ok);
+        assert_eq!(turso_last_error_extended_code(), TursoErrorCode::Ok);
+    }
+}
@@ -0,0 +1,224 @@
+use std::ffi::{c_char, c_void, CString};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use turso_core::{CheckpointMode, Connection, Database};
+
+use crate::host::HostPtr;
+use crate::{wal_error, Error, Result, TursoErrorCode};
+
+/// Host callback told about failed background checkpoints. Receives the user data
+/// pointer, the error code and a message that is only valid during the call. Runs on
+/// a maintenance thread, not on a thread the host created. It may stop or restart the
+/// task, which then doesn't wait for the checkpoint the callback is reporting on.
+pub type CheckpointErrorCallback =
+    extern "C" fn(user_data: *mut c_void, code: TursoErrorCode, message: *const c_char);
+
+/// When the background task checkpoints, and how.
+#[derive(Copy, Clone)]
+pub struct AutoCheckpointConfig {
+    /// Checkpoint once this many frames are waiting to be copied; 0 disables the check.
+    pub frame_threshold: u64,
+    /// Checkpoint once frames have been waiting this long.
+    pub max_age: Option<Duration>,
+    /// How often the WAL is inspected.
+    pub poll_interval: Duration,
+    pub mode: CheckpointMode,
+}
+
+struct ErrorReporter {
+    callback: Option<CheckpointErrorCallback>,
+    user_data: HostPtr,
+}
+
+impl ErrorReporter {
+    fn report(&self, err: &Error) {
+        let Some(callback) = self.callback else {
+            return;
+        };
+        let message = CString::new(err.to_string())
+            .unwrap_or_else(|_| CString::new("Invalid error message").unwrap());
+        callback(self.user_data.0, err.code(), message.as_ptr());
+    }
+}
+
+/// A background checkpoint task on the shared maintenance runtime. Dropping it stops
+/// the task and waits for a checkpoint in progress, so the error callback is never
+/// called afterwards.
+pub struct AutoCheckpoint {
+    shared: Arc<Shared>,
+    handle: Option<tokio::task::JoinHandle<()>>,
+}
+
+// What the task shares with its AutoCheckpoint. The worker is locked for each poll, so
+// taking the lock waits for a poll in progress, and once stopped is set no poll starts.
+struct Shared {
+    stopped: AtomicBool,
+    worker: Mutex<Worker>,
+}
+
+struct Worker {
+    database: Arc<Database>,
+    // Opened on the first poll and kept for the life of the task
+    connection: Option<Arc<Connection>>,
+    tracker: WalTracker,
+    config: AutoCheckpointConfig,
+    reporter: ErrorReporter,
+}
+
+impl AutoCheckpoint {
+    pub fn start(
+        database: Arc<Database>,
+        config: AutoCheckpointConfig,
+        callback: Option<CheckpointErrorCallback>,
+        user_data: *mut c_void,
+    ) -> Result<Self> {
+        let shared = Arc::new(Shared {
+            stopped: AtomicBool::new(false),
+            worker: Mutex::new(Worker {
+                database,
+                connection: None,
+                tracker: WalTracker::default(),
+                config,
+                reporter: ErrorReporter {
+                    callback,
+                    user_data: HostPtr(user_data),
+                },
+            }),
+        });
+
+        #[cfg(target_family = "wasm")]
+        {
+            let _ = shared;
+            Err(Error::CoreError {
+                code: TursoErrorCode::Error,
+                message: "Background checkpointing needs threads, which this platform does not have".to_string(),
+            })
+        }
+
+        #[cfg(not(target_family = "wasm"))]
+        {
+            let handle = runtime()?.spawn(run(shared.clone(), config.poll_interval));
+            Ok(Self { shared, handle: Some(handle) })
+        }
+    }
+}
+
+impl Drop for AutoCheckpoint {
+    fn drop(&mut self) {
+        let Some(handle) = self.handle.take() else {
+            return;
+        };
+        self.shared.stopped.store(true, Ordering::Release);
+        handle.abort();
+
+        // On a maintenance thread this is the error callback stopping its own task: the
+        // poll it reports from holds the worker until the callback returns, and polls no
+        // more after that
+        if tokio::runtime::Handle::try_current().is_ok() {
+            return;
+        }
+        // Otherwise wait for a poll in progress, which may still call the callback
+        drop(self.shared.worker.lock());
+    }
+}
+
+// One small runtime shared by every database, started on first use
+#[cfg(not(target_family = "wasm"))]
+static RUNTIME: std::sync::OnceLock<tokio::runtime::Runtime> = std::sync::OnceLock::new();
+
+#[cfg(not(target_family = "wasm"))]
+fn runtime() -> Result<&'static tokio::runtime::Runtime> {
+    if let Some(runtime) = RUNTIME.get() {
+        return Ok(runtime);
+    }
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("turso-maintenance")
+        .enable_time()
+        .build()
+        .map_err(|e| Error::SqlExecutionFailure(format!("Failed to start maintenance runtime: {e}")))?;
+    Ok(RUNTIME.get_or_init(|| runtime))
+}
+
+#[cfg(not(target_family = "wasm"))]
+async fn run(shared: Arc<Shared>, poll_interval: Duration) {
+    let mut interval = tokio::time::interval(poll_interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+        let shared = shared.clone();
+        // A checkpoint blocks on IO, so it runs on the blocking pool rather than on the
+        // runtime's only worker, which every database's task shares
+        if tokio::task::spawn_blocking(move || shared.poll()).await.is_err() {
+            return;
+        }
+    }
+}
+
+impl Shared {
+    fn poll(&self) {
+        let Ok(mut worker) = self.worker.lock() else {
+            return;
+        };
+        if self.stopped.load(Ordering::Acquire) {
+            return;
+        }
+
+        match worker.checkpoint_if_due() {
+            Ok(()) => {}
+            // Another connection holds the WAL; try again on the next tick
+            Err(err) if err.code() == TursoErrorCode::Busy => {}
+            Err(err) => worker.reporter.report(&err),
+        }
+    }
+}
+
+impl Worker {
+    fn checkpoint_if_due(&mut self) -> Result<()> {
+        let conn = match &self.connection {
+            Some(conn) => conn.clone(),
+            None => self.connection.insert(self.database.connect()?).clone(),
+        };
+        self.tracker.poll(&conn, &self.config)
+    }
+}
+
+// Tracks how many WAL frames are still waiting to be checkpointed, and since when
+#[derive(Default)]
+struct WalTracker {
+    backfilled: u64,
+    pending_since: Option<Instant>,
+}
+
+impl WalTracker {
+    fn poll(&mut self, conn: &Arc<Connection>, config: &AutoCheckpointConfig) -> Result<()> {
+        let frames = conn.wal_frame_count().map_err(wal_error)?;
+        if frames < self.backfilled {
+            // A writer restarted the WAL from the beginning
+            self.backfilled = 0;
+        }
+        let pending = frames - self.backfilled;
+        if pending == 0 {
+            self.pending_since = None;
+            return Ok(());
+        }
+
+        let pending_since = *self.pending_since.get_or_insert_with(Instant::now);
+        let due = (config.frame_threshold > 0 && pending >= config.frame_threshold)
+            || config.max_age.is_some_and(|max_age| pending_since.elapsed() >= max_age);
+        if !due {
+            return Ok(());
+        }
+
+        let checkpoint = conn.checkpoint(config.mode).map_err(wal_error)?;
+        self.backfilled = checkpoint.num_checkpointed_frames;
+        if checkpoint.num_checkpointed_frames >= checkpoint.num_wal_frames {
+            self.pending_since = None;
+        }
+        Ok(())
+    }
+}
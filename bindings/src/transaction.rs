@@ -1,3 +1,9 @@
+use std::sync::{Arc, Mutex};
+
+use turso_core::Connection;
+
+use crate::{begin_transaction, commit_transaction, rollback_transaction, ConnectionState, Error, Result};
+
 /// Options for transaction behavior. See [BEGIN
 /// TRANSACTION](http://www.sqlite.org/lang_transaction.html) for details.
 #[derive(Copy, Clone)]
@@ -30,4 +36,155 @@ pub enum DropBehavior {
 
     /// Panic. Used to enforce intentional behavior during development.
     Panic,
-}
\ No newline at end of file
+}
+
+impl TransactionBehavior {
+    pub(crate) fn begin_sql(self) -> &'static str {
+        match self {
+            TransactionBehavior::Deferred => "BEGIN DEFERRED",
+            TransactionBehavior::Immediate => "BEGIN IMMEDIATE",
+            TransactionBehavior::Exclusive => "BEGIN EXCLUSIVE",
+        }
+    }
+}
+
+impl TryFrom<i32> for TransactionBehavior {
+    type Error = Error;
+
+    fn try_from(value: i32) -> Result<Self> {
+        match value {
+            0 => Ok(TransactionBehavior::Deferred),
+            1 => Ok(TransactionBehavior::Immediate),
+            2 => Ok(TransactionBehavior::Exclusive),
+            _ => Err(Error::misuse("Invalid transaction behavior")),
+        }
+    }
+}
+
+impl TryFrom<i32> for DropBehavior {
+    type Error = Error;
+
+    fn try_from(value: i32) -> Result<Self> {
+        match value {
+            0 => Ok(DropBehavior::Rollback),
+            1 => Ok(DropBehavior::Commit),
+            2 => Ok(DropBehavior::Ignore),
+            3 => Ok(DropBehavior::Panic),
+            _ => Err(Error::misuse("Invalid drop behavior")),
+        }
+    }
+}
+
+/// A transaction handed out across the FFI boundary. If it is finalized or dropped
+/// without being committed or rolled back, its [DropBehavior] decides what happens.
+///
+/// The transaction can also be ended behind the handle's back, e.g. by COMMIT issued
+/// as SQL on the same connection. The handle remembers the connection's transaction
+/// generation at begin and never commits or rolls back once it has moved on, since the
+/// open transaction, if any, then belongs to another unit of work.
+pub(crate) struct Transaction {
+    connection: Arc<Mutex<Arc<Connection>>>,
+    state: Arc<ConnectionState>,
+    generation: u64,
+    drop_behavior: DropBehavior,
+    finished: bool,
+}
+
+impl Transaction {
+    pub(crate) fn begin(
+        connection: Arc<Mutex<Arc<Connection>>>,
+        state: Arc<ConnectionState>,
+        behavior: TransactionBehavior,
+        drop_behavior: DropBehavior,
+    ) -> Result<Self> {
+        let generation = {
+            let conn = connection.lock()
+                .map(|conn| Arc::clone(&conn))
+                .map_err(|e| Error::MutexError(e.to_string()))?;
+            begin_transaction(&conn, behavior, &state)?
+        };
+
+        Ok(Self {
+            connection,
+            state,
+            generation,
+            drop_behavior,
+            finished: false,
+        })
+    }
+
+    pub(crate) fn commit(&mut self) -> Result<()> {
+        self.finish(true)
+    }
+
+    pub(crate) fn rollback(&mut self) -> Result<()> {
+        self.finish(false)
+    }
+
+    /// Applies the drop behavior if the transaction has not been finished yet.
+    pub(crate) fn finish_with_drop_behavior(&mut self) -> Result<()> {
+        if self.finished {
+            return Ok(());
+        }
+
+        match self.drop_behavior {
+            DropBehavior::Rollback => self.end(false).map(|_| ()),
+            DropBehavior::Commit => self.end(true).map(|_| ()),
+            DropBehavior::Ignore => {
+                self.finished = true;
+                Ok(())
+            }
+            DropBehavior::Panic => match self.end(false) {
+                // Ended some other way, so it was not abandoned
+                Ok(false) => Ok(()),
+                // Unwinding cannot cross the FFI boundary, so report misuse instead and
+                // roll back rather than leave the connection inside a transaction
+                _ => Err(Error::misuse("transaction finalized without commit or rollback")),
+            },
+        }
+    }
+
+    fn finish(&mut self, commit: bool) -> Result<()> {
+        if self.finished {
+            return Err(Error::misuse("transaction is already finished"));
+        }
+
+        if self.end(commit)? {
+            Ok(())
+        } else {
+            Err(Error::misuse("transaction was already ended on its connection"))
+        }
+    }
+
+    // Commits or rolls back the transaction. Returns false, touching nothing, if it was
+    // already ended some other way.
+    fn end(&mut self, commit: bool) -> Result<bool> {
+        // Cloned out so the lock isn't held while COMMIT waits on a busy database
+        let conn = self.connection.lock()
+            .map(|conn| Arc::clone(&conn))
+            .map_err(|e| Error::MutexError(e.to_string()))?;
+
+        if conn.get_auto_commit() || self.state.transaction_generation() != self.generation {
+            self.finished = true;
+            return Ok(false);
+        }
+
+        let result = if commit {
+            commit_transaction(&conn, &self.state)
+        } else {
+            rollback_transaction(&conn, &self.state)
+        };
+
+        // A failed commit can still end the transaction, e.g. when the commit hook vetoes it
+        if result.is_ok() || conn.get_auto_commit() {
+            self.finished = true;
+        }
+        result.map(|()| true)
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        let _ = self.finish_with_drop_behavior();
+    }
+}
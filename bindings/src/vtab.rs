@@ -0,0 +1,394 @@
+use std::ffi::{c_char, c_void, CString};
+use std::sync::{Arc, Mutex};
+
+use turso_core::Connection;
+use turso_ext::{
+    BestIdxFn, Conn, ConstraintInfo, ConstraintUsage, ExtIndexInfo, IndexInfo, OrderByInfo, ResultCode,
+    VTabCreateResult, VTabKind, VTabModuleImpl, Value as ExtValue, VtabFnCreate,
+};
+
+use crate::functions::{self, FunctionContext};
+use crate::host::{self, HostPtr, NamedSlots};
+use crate::{Error, Result, TursoErrorCode, TursoIndexConstraint, TursoIndexPlan};
+
+/// Creates the host's table for `CREATE VIRTUAL TABLE ... USING module(args)`. Receives
+/// the module's user data, a context to declare the columns through
+/// (`turso_vtab_declare`) and the module arguments as text value handles. Returns the
+/// host's table handle, or null on failure.
+pub type VTabConnectCallback =
+    extern "C" fn(user_data: *mut c_void, context: *mut c_void, argc: i32, argv: *const *const c_void) -> *mut c_void;
+
+/// Chooses how to scan for a query: sets `argv_index` and `omit` on the constraints it
+/// will use and fills in the plan. Returning non-zero keeps the default full scan.
+pub type VTabBestIndexCallback = extern "C" fn(
+    user_data: *mut c_void,
+    constraints: *mut TursoIndexConstraint,
+    n_constraints: i32,
+    plan: *mut TursoIndexPlan,
+) -> i32;
+
+/// Called with a table handle when turso_core drops the table.
+pub type VTabDisconnectCallback = extern "C" fn(table: *mut c_void);
+
+/// Opens a cursor on a table, returning the host's cursor handle or null on failure.
+pub type VTabOpenCallback = extern "C" fn(table: *mut c_void) -> *mut c_void;
+
+/// Closes a cursor.
+pub type VTabCloseCallback = extern "C" fn(cursor: *mut c_void);
+
+/// Starts a scan with the plan's idx_num and the values of the constraints that were
+/// given an argv_index, in that order. Returns zero on success.
+pub type VTabFilterCallback =
+    extern "C" fn(cursor: *mut c_void, idx_num: i32, argc: i32, argv: *const *const c_void) -> i32;
+
+/// Moves to the next row. Returns zero on success.
+pub type VTabNextCallback = extern "C" fn(cursor: *mut c_void) -> i32;
+
+/// Whether the cursor has moved past the last row.
+pub type VTabEofCallback = extern "C" fn(cursor: *mut c_void) -> bool;
+
+/// Reports a column of the current row through `turso_result_*`.
+pub type VTabColumnCallback = extern "C" fn(cursor: *mut c_void, context: *mut c_void, column: i32);
+
+/// Returns the rowid of the current row.
+pub type VTabRowidCallback = extern "C" fn(cursor: *mut c_void) -> i64;
+
+/// The host side of a virtual table module.
+#[derive(Copy, Clone)]
+pub struct ModuleCallbacks {
+    pub connect: VTabConnectCallback,
+    pub best_index: Option<VTabBestIndexCallback>,
+    pub disconnect: Option<VTabDisconnectCallback>,
+    pub open: VTabOpenCallback,
+    pub close: VTabCloseCallback,
+    pub filter: VTabFilterCallback,
+    pub next: VTabNextCallback,
+    pub eof: VTabEofCallback,
+    pub column: VTabColumnCallback,
+    pub rowid: VTabRowidCallback,
+}
+
+/// Where a connect callback declares the table's columns.
+pub struct ModuleContext {
+    schema: Option<String>,
+}
+
+impl ModuleContext {
+    pub(crate) fn declare(&mut self, schema: String) {
+        self.schema = Some(schema);
+    }
+}
+
+// Like functions, every distinct module name gets a trampoline, here for create and
+// best_idx, the only module entry points turso_core calls without a table or cursor
+const MAX_MODULES: usize = 16;
+
+#[derive(Clone)]
+struct Registration {
+    // turso_core keeps the name pointer it is given, so every copy shares the string
+    name: Arc<CString>,
+    callbacks: ModuleCallbacks,
+    user_data: HostPtr,
+}
+
+// What turso_core holds as a table or cursor
+struct Table {
+    callbacks: ModuleCallbacks,
+    handle: *mut c_void,
+}
+
+struct Cursor {
+    callbacks: ModuleCallbacks,
+    handle: *mut c_void,
+}
+
+static MODULE_SLOTS: Mutex<NamedSlots<Registration, MAX_MODULES>> = Mutex::new(NamedSlots::new());
+
+macro_rules! trampolines {
+    ($($slot:literal)*) => {
+        const CREATE_TRAMPOLINES: [VtabFnCreate; MAX_MODULES] = [$({
+            unsafe extern "C" fn trampoline(args: *const ExtValue, argc: i32) -> VTabCreateResult {
+                create_table($slot, args, argc)
+            }
+            trampoline
+        }),*];
+
+        const BEST_INDEX_TRAMPOLINES: [BestIdxFn; MAX_MODULES] = [$({
+            unsafe extern "C" fn trampoline(
+                constraints: *const ConstraintInfo,
+                constraint_len: i32,
+                order_by: *const OrderByInfo,
+                order_by_len: i32,
+            ) -> ExtIndexInfo {
+                let _ = (order_by, order_by_len);
+                best_index($slot, constraints, constraint_len)
+            }
+            trampoline
+        }),*];
+    };
+}
+
+trampolines!(0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15);
+
+/// Registers a read-only virtual table module on the connection. owner identifies the
+/// registering connection, as for [functions::register_scalar_function].
+pub(crate) fn register_module(
+    conn: &Connection,
+    owner: usize,
+    name: &str,
+    callbacks: ModuleCallbacks,
+    user_data: *mut c_void,
+) -> Result<()> {
+    let c_name = CString::new(name)
+        .map_err(|_| Error::SqlExecutionFailure("Invalid module name".to_string()))?;
+    let registration = Registration {
+        name: Arc::new(c_name),
+        callbacks,
+        user_data: HostPtr(user_data),
+    };
+
+    let slot = MODULE_SLOTS.lock()
+        .map_err(|e| Error::MutexError(e.to_string()))?
+        .claim(owner, name, registration.clone())
+        .ok_or_else(|| Error::CoreError {
+            code: TursoErrorCode::Full,
+            message: format!("Cannot register more than {MAX_MODULES} distinct module names"),
+        })?;
+
+    let module = VTabModuleImpl {
+        name: registration.name.as_ptr(),
+        create: CREATE_TRAMPOLINES[slot],
+        open: open_cursor,
+        close: close_cursor,
+        filter,
+        column,
+        next,
+        eof,
+        update,
+        rowid,
+        destroy: destroy_table,
+        best_idx: BEST_INDEX_TRAMPOLINES[slot],
+    };
+
+    let api = conn._build_turso_ext();
+    let rc = unsafe { (api.register_vtab_module)(api.ctx, registration.name.as_ptr(), module, VTabKind::VirtualTable) };
+    conn._free_extension_ctx(api);
+
+    if rc.is_ok() {
+        return Ok(());
+    }
+    if let Ok(mut slots) = MODULE_SLOTS.lock() {
+        slots.remove(slot, owner);
+    }
+    Err(Error::SqlExecutionFailure(format!("Failed to register module {name}")))
+}
+
+/// Drops every module registered by owner, see [functions::release_functions].
+pub(crate) fn release_modules(owner: usize) {
+    if let Ok(mut slots) = MODULE_SLOTS.lock() {
+        slots.release(owner);
+    }
+}
+
+fn lookup(slot: usize) -> Option<Registration> {
+    MODULE_SLOTS.lock().ok()?.get(slot, host::current_connection())
+}
+
+fn create_table(slot: usize, args: *const ExtValue, argc: i32) -> VTabCreateResult {
+    let failed = VTabCreateResult {
+        code: ResultCode::Error,
+        schema: std::ptr::null(),
+        table: std::ptr::null(),
+    };
+    let Some(registration) = lookup(slot) else {
+        return failed;
+    };
+    let callbacks = registration.callbacks;
+
+    let args = functions::collect_args(argc, args);
+    let handles = functions::arg_handles(&args);
+    let mut context = ModuleContext { schema: None };
+    let handle = (callbacks.connect)(
+        registration.user_data.0,
+        &mut context as *mut ModuleContext as *mut c_void,
+        args.len() as i32,
+        handles.as_ptr(),
+    );
+    if handle.is_null() {
+        return failed;
+    }
+
+    let Some(schema) = context.schema.and_then(|schema| CString::new(schema).ok()) else {
+        // A table without columns can't be used, so give it straight back
+        if let Some(disconnect) = callbacks.disconnect {
+            disconnect(handle);
+        }
+        return failed;
+    };
+
+    // turso_core takes ownership of the schema string
+    VTabCreateResult {
+        code: ResultCode::OK,
+        schema: schema.into_raw(),
+        table: Box::into_raw(Box::new(Table { callbacks, handle })) as *const c_void,
+    }
+}
+
+fn best_index(slot: usize, constraints: *const ConstraintInfo, constraint_len: i32) -> ExtIndexInfo {
+    let constraints = if constraints.is_null() || constraint_len <= 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(constraints, constraint_len as usize) }
+    };
+
+    let mut info = IndexInfo {
+        constraint_usages: constraints.iter()
+            .map(|_| ConstraintUsage { argv_index: None, omit: false })
+            .collect(),
+        ..IndexInfo::default()
+    };
+
+    let Some(registration) = lookup(slot) else {
+        return info.to_ffi();
+    };
+    let Some(callback) = registration.callbacks.best_index else {
+        return info.to_ffi();
+    };
+
+    let mut host_constraints: Vec<TursoIndexConstraint> = constraints.iter()
+        .map(|constraint| TursoIndexConstraint {
+            column: constraint.column_index as i32,
+            op: constraint.op as i32,
+            usable: constraint.usable,
+            argv_index: 0,
+            omit: false,
+        })
+        .collect();
+    let mut plan = TursoIndexPlan {
+        idx_num: info.idx_num,
+        estimated_cost: info.estimated_cost,
+        estimated_rows: i64::from(info.estimated_rows),
+    };
+
+    if callback(registration.user_data.0, host_constraints.as_mut_ptr(), host_constraints.len() as i32, &mut plan) == 0 {
+        info.idx_num = plan.idx_num;
+        info.estimated_cost = plan.estimated_cost;
+        info.estimated_rows = plan.estimated_rows.clamp(0, i64::from(u32::MAX)) as u32;
+        info.constraint_usages = host_constraints.iter()
+            .map(|constraint| ConstraintUsage {
+                argv_index: (constraint.argv_index > 0).then_some(constraint.argv_index as u32),
+                omit: constraint.omit,
+            })
+            .collect();
+    }
+    info.to_ffi()
+}
+
+unsafe extern "C" fn open_cursor(table: *const c_void, _conn: *mut Conn) -> *const c_void {
+    if table.is_null() {
+        return std::ptr::null();
+    }
+    let table = &*(table as *const Table);
+
+    let handle = (table.callbacks.open)(table.handle);
+    if handle.is_null() {
+        return std::ptr::null();
+    }
+    Box::into_raw(Box::new(Cursor {
+        callbacks: table.callbacks,
+        handle,
+    })) as *const c_void
+}
+
+unsafe extern "C" fn close_cursor(cursor: *const c_void) -> ResultCode {
+    if cursor.is_null() {
+        return ResultCode::Error;
+    }
+    let cursor = Box::from_raw(cursor as *mut Cursor);
+    (cursor.callbacks.close)(cursor.handle);
+    ResultCode::OK
+}
+
+unsafe extern "C" fn filter(
+    cursor: *const c_void,
+    argc: i32,
+    argv: *const ExtValue,
+    _idx_str: *const c_char,
+    idx_num: i32,
+) -> ResultCode {
+    if cursor.is_null() {
+        return ResultCode::Error;
+    }
+    let cursor = &*(cursor as *const Cursor);
+
+    let args = functions::collect_args(argc, argv);
+    let handles = functions::arg_handles(&args);
+    if (cursor.callbacks.filter)(cursor.handle, idx_num, args.len() as i32, handles.as_ptr()) != 0 {
+        return ResultCode::Error;
+    }
+    row_status(cursor)
+}
+
+unsafe extern "C" fn next(cursor: *const c_void) -> ResultCode {
+    if cursor.is_null() {
+        return ResultCode::Error;
+    }
+    let cursor = &*(cursor as *const Cursor);
+
+    if (cursor.callbacks.next)(cursor.handle) != 0 {
+        return ResultCode::Error;
+    }
+    row_status(cursor)
+}
+
+// turso_core expects filter and next to say whether they reached the end
+fn row_status(cursor: &Cursor) -> ResultCode {
+    if (cursor.callbacks.eof)(cursor.handle) {
+        ResultCode::EOF
+    } else {
+        ResultCode::OK
+    }
+}
+
+unsafe extern "C" fn eof(cursor: *const c_void) -> bool {
+    if cursor.is_null() {
+        return true;
+    }
+    let cursor = &*(cursor as *const Cursor);
+    (cursor.callbacks.eof)(cursor.handle)
+}
+
+unsafe extern "C" fn column(cursor: *const c_void, idx: u32) -> ExtValue {
+    if cursor.is_null() {
+        return ExtValue::error_with_message("Invalid cursor".to_string());
+    }
+    let cursor = &*(cursor as *const Cursor);
+
+    let mut context = FunctionContext::for_value();
+    (cursor.callbacks.column)(cursor.handle, &mut context as *mut FunctionContext as *mut c_void, idx as i32);
+    functions::context_result(context)
+}
+
+unsafe extern "C" fn rowid(cursor: *const c_void) -> i64 {
+    if cursor.is_null() {
+        return 0;
+    }
+    let cursor = &*(cursor as *const Cursor);
+    (cursor.callbacks.rowid)(cursor.handle)
+}
+
+// Host tables are read-only
+unsafe extern "C" fn update(_table: *const c_void, _argc: i32, _argv: *const ExtValue, _rowid: *mut i64) -> ResultCode {
+    ResultCode::Error
+}
+
+unsafe extern "C" fn destroy_table(table: *const c_void) -> ResultCode {
+    if table.is_null() {
+        return ResultCode::Error;
+    }
+    let table = Box::from_raw(table as *mut Table);
+    if let Some(disconnect) = table.callbacks.disconnect {
+        disconnect(table.handle);
+    }
+    ResultCode::OK
+}
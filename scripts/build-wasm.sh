@@ -31,7 +31,7 @@ cp Cargo.toml Cargo.toml.backup
 
 # Update crate-type to staticlib and tokio features for WASM build
 sed -e 's/crate-type = \["cdylib"\]/crate-type = ["staticlib"]/' \
-    -e 's/tokio = { version = "1.0", features = \["rt", "rt-multi-thread"\] }/tokio = { version = "1.0", features = ["rt", "sync", "macros"] }/' \
+    -e 's/tokio = { version = "1.0", features = \["rt", "rt-multi-thread", "time"\] }/tokio = { version = "1.0", features = ["rt", "sync", "macros"] }/' \
     Cargo.toml.backup > Cargo.toml
 
 # Build for WASM target with compatible features
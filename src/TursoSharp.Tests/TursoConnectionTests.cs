@@ -54,6 +54,23 @@ public class TursoConnectionTests
         // No exception should be thrown
     }
 
+    [TestMethod]
+    public void Execute_InsertReturning_ShouldSucceed()
+    {
+        _connection!.Execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL)");
+        _connection.Execute("INSERT INTO users (name) VALUES ('John Doe') RETURNING id");
+
+        var count = _connection.QueryScalarInt64("SELECT COUNT(*) FROM users");
+        Assert.AreEqual(1L, count);
+    }
+
+    [TestMethod]
+    public void Execute_StatementReturningRows_ShouldSucceed()
+    {
+        _connection!.Execute("SELECT 1");
+        // Rows are discarded, no exception should be thrown
+    }
+
     [TestMethod]
     public void QueryScalarInt64_Count_ShouldReturnCorrectValue()
     {
@@ -124,6 +141,116 @@ public class TursoConnectionTests
         Assert.IsNull(name);
     }
 
+    [TestMethod]
+    public void ExecuteBatch_ShouldRunEveryStatement()
+    {
+        // Act
+        var rowsChanged = _connection!.ExecuteBatch("""
+            CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL);
+            INSERT INTO users (name) VALUES ('John Doe');
+            INSERT INTO users (name) VALUES ('Jane Smith');
+            -- a comment between statements
+            UPDATE users SET name = 'Jane Doe' WHERE name = 'Jane Smith';
+            """);
+
+        // Assert
+        Assert.AreEqual(3L, rowsChanged);
+        Assert.AreEqual(2L, _connection.QueryScalarInt64("SELECT COUNT(*) FROM users"));
+        Assert.AreEqual(1L, _connection.QueryScalarInt64("SELECT COUNT(*) FROM users WHERE name = 'Jane Doe'"));
+    }
+
+    [TestMethod]
+    public void ExecuteBatch_Transactional_WithFailingStatement_ShouldReportIndexAndRollBack()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL)");
+
+        // Act
+        var exception = Assert.ThrowsExactly<TursoBatchException>(() => _connection.ExecuteBatch("""
+            INSERT INTO users (name) VALUES ('John Doe');
+            INSERT INTO users (name) VALUES ('Jane Smith');
+            INSERT INTO missing_table (name) VALUES ('Nobody');
+            INSERT INTO users (name) VALUES ('Never Inserted');
+            """));
+
+        // Assert
+        Assert.AreEqual(2, exception.FailedStatementIndex);
+        Assert.AreEqual(0L, exception.RowsChanged);
+        Assert.AreEqual(0L, _connection.QueryScalarInt64("SELECT COUNT(*) FROM users"));
+        Assert.IsTrue(_connection.IsAutoCommit);
+    }
+
+    [TestMethod]
+    public void ExecuteBatch_NonTransactional_WithFailingStatement_ShouldKeepEarlierChanges()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL)");
+
+        // Act
+        var exception = Assert.ThrowsExactly<TursoBatchException>(() => _connection.ExecuteBatch("""
+            INSERT INTO users (name) VALUES ('John Doe');
+            INSERT INTO users (name) VALUES ('Jane Smith');
+            INSERT INTO missing_table (name) VALUES ('Nobody');
+            INSERT INTO users (name) VALUES ('Never Inserted');
+            """, transactional: false));
+
+        // Assert
+        Assert.AreEqual(2, exception.FailedStatementIndex);
+        Assert.AreEqual(2L, exception.RowsChanged);
+        Assert.AreEqual(2L, _connection.QueryScalarInt64("SELECT COUNT(*) FROM users"));
+    }
+
+    [TestMethod]
+    public void Interrupt_FromAnotherThreadBetweenReads_ShouldStopResultSet()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE numbers (value INTEGER)");
+        _connection.Execute("INSERT INTO numbers (value) VALUES (1), (2), (3)");
+        using var resultSet = _connection.Query("SELECT value FROM numbers");
+        Assert.IsTrue(resultSet.Read());
+
+        // Act
+        Task.Run(() => _connection.Interrupt()).Wait();
+
+        // Assert
+        var exception = Assert.ThrowsExactly<TursoException>(() => resultSet.Read());
+        Assert.AreEqual(TursoErrorCode.Interrupt, exception.ErrorCode);
+    }
+
+    [TestMethod]
+    public void Interrupt_WhenNothingIsRunning_ShouldNotAffectLaterQueries()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE numbers (value INTEGER)");
+        _connection.Execute("INSERT INTO numbers (value) VALUES (1), (2), (3)");
+
+        // Act
+        _connection.Interrupt();
+
+        // Assert
+        Assert.AreEqual(3L, _connection.QueryScalarInt64("SELECT COUNT(*) FROM numbers"));
+        using var resultSet = _connection.Query("SELECT value FROM numbers");
+        Assert.AreEqual(3, resultSet.Count());
+    }
+
+    [TestMethod]
+    public void Interrupt_AfterResultSetIsDisposed_ShouldNotAffectLaterQueries()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE numbers (value INTEGER)");
+        _connection.Execute("INSERT INTO numbers (value) VALUES (1), (2), (3)");
+        using (var resultSet = _connection.Query("SELECT value FROM numbers"))
+        {
+            Assert.IsTrue(resultSet.Read());
+        }
+
+        // Act
+        _connection.Interrupt();
+
+        // Assert
+        Assert.AreEqual(3L, _connection.QueryScalarInt64("SELECT COUNT(*) FROM numbers"));
+    }
+
     [TestMethod]
     public void Execute_WithNullSql_ShouldThrowArgumentException()
     {
@@ -178,4 +305,210 @@ public class TursoConnectionTests
         _connection!.Dispose();
         _connection.Dispose(); // Should not throw
     }
-}
\ No newline at end of file
+
+    [TestMethod]
+    public void LastInsertRowId_AfterInsert_ShouldReturnNewRowId()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)");
+        _connection.Execute("INSERT INTO users (id, name) VALUES (41, 'Alice')");
+
+        // Act
+        _connection.Execute("INSERT INTO users (name) VALUES ('Bob')");
+
+        // Assert
+        Assert.AreEqual(42L, _connection.LastInsertRowId);
+    }
+
+    [TestMethod]
+    public void Changes_AfterUpdate_ShouldCountRowsOfLastStatement()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE users (id INTEGER PRIMARY KEY, active INTEGER)");
+        _connection.Execute("INSERT INTO users (active) VALUES (0), (0), (1)");
+        var totalBefore = _connection.TotalChanges;
+
+        // Act
+        _connection.Execute("UPDATE users SET active = 1 WHERE active = 0");
+
+        // Assert
+        Assert.AreEqual(2L, _connection.Changes);
+        Assert.AreEqual(totalBefore + 2, _connection.TotalChanges);
+    }
+
+    [TestMethod]
+    public void Statement_AfterInsertIsDone_ShouldReportRowIdAndChanges()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)");
+        using var statement = _connection.Prepare("INSERT INTO users (id, name) VALUES (7, 'Carol')");
+
+        // Act
+        var status = statement.Step();
+
+        // Assert
+        Assert.AreEqual(0, status);
+        Assert.AreEqual(7L, statement.LastInsertRowId);
+        Assert.AreEqual(1L, statement.Changes);
+    }
+
+    [TestMethod]
+    public void SetBusyTimeout_WhenLockIsReleasedInTime_ShouldRetryUntilWriteSucceeds()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE test (id INTEGER PRIMARY KEY)");
+        using var writer = _database!.Connect();
+        writer.BeginTransaction(TursoTransactionBehavior.Immediate);
+        _connection.SetBusyTimeout(TimeSpan.FromSeconds(10));
+
+        // Act
+        var release = Task.Run(async () =>
+        {
+            await Task.Delay(200);
+            writer.CommitTransaction();
+        });
+        _connection.Execute("INSERT INTO test (id) VALUES (1)");
+        release.Wait();
+
+        // Assert
+        Assert.AreEqual(1L, _connection.QueryScalarInt64("SELECT COUNT(*) FROM test"));
+    }
+
+    [TestMethod]
+    public void SetBusyTimeout_WhenLockIsHeldTooLong_ShouldFailWithBusy()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE test (id INTEGER PRIMARY KEY)");
+        using var writer = _database!.Connect();
+        writer.BeginTransaction(TursoTransactionBehavior.Immediate);
+        _connection.SetBusyTimeout(TimeSpan.FromMilliseconds(100));
+        var stopwatch = System.Diagnostics.Stopwatch.StartNew();
+
+        // Act
+        var exception = Assert.ThrowsExactly<TursoException>(() =>
+            _connection.Execute("INSERT INTO test (id) VALUES (1)"));
+        stopwatch.Stop();
+        writer.RollbackTransaction();
+
+        // Assert
+        Assert.AreEqual(TursoErrorCode.Busy, exception.ErrorCode);
+        Assert.IsTrue(stopwatch.ElapsedMilliseconds >= 100);
+    }
+
+    [TestMethod]
+    public void SetBusyHandler_WhenHandlerGivesUp_ShouldFailWithBusy()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE test (id INTEGER PRIMARY KEY)");
+        using var writer = _database!.Connect();
+        writer.BeginTransaction(TursoTransactionBehavior.Immediate);
+        var attempts = new List<int>();
+        _connection.SetBusyHandler(attempt =>
+        {
+            attempts.Add(attempt);
+            return attempt < 2;
+        });
+
+        // Act
+        var exception = Assert.ThrowsExactly<TursoException>(() =>
+            _connection.Execute("INSERT INTO test (id) VALUES (1)"));
+        writer.RollbackTransaction();
+
+        // Assert
+        Assert.AreEqual(TursoErrorCode.Busy, exception.ErrorCode);
+        CollectionAssert.AreEqual(new[] { 0, 1, 2 }, attempts);
+    }
+
+    [TestMethod]
+    public void SetBusyHandler_WhenLockIsReleased_ShouldRetryUntilWriteSucceeds()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE test (id INTEGER PRIMARY KEY)");
+        using var writer = _database!.Connect();
+        writer.BeginTransaction(TursoTransactionBehavior.Immediate);
+        var calls = 0;
+        _connection.SetBusyHandler(attempt =>
+        {
+            calls++;
+            if (attempt == 0)
+            {
+                writer.CommitTransaction();
+            }
+            return true;
+        });
+
+        // Act
+        _connection.Execute("INSERT INTO test (id) VALUES (1)");
+
+        // Assert
+        Assert.IsTrue(calls > 0);
+        Assert.AreEqual(1L, _connection.QueryScalarInt64("SELECT COUNT(*) FROM test"));
+    }
+
+    [TestMethod]
+    public void Interrupt_WhileWaitingOnBusyTimeout_ShouldStopTheWait()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE test (id INTEGER PRIMARY KEY)");
+        using var writer = _database!.Connect();
+        writer.BeginTransaction(TursoTransactionBehavior.Immediate);
+        _connection.SetBusyTimeout(TimeSpan.FromSeconds(30));
+        var stopwatch = System.Diagnostics.Stopwatch.StartNew();
+
+        // Act
+        var interrupt = Task.Run(async () =>
+        {
+            await Task.Delay(200);
+            _connection.Interrupt();
+        });
+        var exception = Assert.ThrowsExactly<TursoException>(() =>
+            _connection.Execute("INSERT INTO test (id) VALUES (1)"));
+        stopwatch.Stop();
+        interrupt.Wait();
+        writer.RollbackTransaction();
+
+        // Assert
+        Assert.AreEqual(TursoErrorCode.Interrupt, exception.ErrorCode);
+        Assert.IsTrue(stopwatch.Elapsed < TimeSpan.FromSeconds(10));
+    }
+
+    [TestMethod]
+    public void LoadExtension_WhenNotEnabled_ShouldThrow()
+    {
+        // Act
+        var exception = Assert.ThrowsExactly<TursoException>(() =>
+            _connection!.LoadExtension("missing_extension.so"));
+
+        // Assert
+        Assert.IsTrue(exception.Message.Contains("not enabled"));
+    }
+
+    [TestMethod]
+    public void LoadExtension_WhenEnabled_ShouldTryToLoadTheLibrary()
+    {
+        // Arrange
+        _connection!.EnableExtensionLoading(true);
+
+        // Act
+        var exception = Assert.ThrowsExactly<TursoException>(() =>
+            _connection.LoadExtension("missing_extension.so"));
+
+        // Assert
+        Assert.IsFalse(exception.Message.Contains("not enabled"));
+    }
+
+    [TestMethod]
+    public void LoadExtension_WhenDisabledAgain_ShouldThrow()
+    {
+        // Arrange
+        _connection!.EnableExtensionLoading(true);
+        _connection.EnableExtensionLoading(false);
+
+        // Act
+        var exception = Assert.ThrowsExactly<TursoException>(() =>
+            _connection.LoadExtension("missing_extension.so"));
+
+        // Assert
+        Assert.IsTrue(exception.Message.Contains("not enabled"));
+    }
+}
@@ -0,0 +1,195 @@
+namespace TursoSharp.Tests;
+
+[TestClass]
+public class TursoCustomFunctionTests
+{
+    private TursoDatabase? _database;
+    private TursoConnection? _connection;
+
+    [TestInitialize]
+    public void Setup()
+    {
+        _database = TursoDatabase.OpenMemory();
+        _connection = _database.Connect();
+    }
+
+    [TestCleanup]
+    public void Cleanup()
+    {
+        _connection?.Dispose();
+        _database?.Dispose();
+    }
+
+    [TestMethod]
+    public void CreateScalarFunction_ShouldBeCallableFromSql()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE numbers (value INTEGER)");
+        _connection.Execute("INSERT INTO numbers (value) VALUES (1), (2), (3)");
+
+        // Act
+        _connection.CreateScalarFunction("double_it", 1, args => (long)args[0]! * 2, deterministic: true);
+
+        // Assert
+        Assert.AreEqual(42L, _connection.QueryScalarInt64("SELECT double_it(21)"));
+        Assert.AreEqual(12L, _connection.QueryScalarInt64("SELECT SUM(double_it(value)) FROM numbers"));
+    }
+
+    [TestMethod]
+    public void CreateScalarFunction_ShouldPassAndReturnEachValueType()
+    {
+        // Arrange
+        object?[]? received = null;
+        _connection!.CreateScalarFunction("describe_args", -1, args =>
+        {
+            received = args;
+            return string.Join(",", args.Select(arg => arg?.GetType().Name ?? "null"));
+        });
+
+        // Act
+        var result = _connection.QueryScalarString("SELECT describe_args(1, 2.5, 'text', x'0102', NULL)");
+
+        // Assert
+        Assert.AreEqual("Int64,Double,String,Byte[],null", result);
+        Assert.AreEqual(2.5, received![1]);
+        Assert.AreEqual("text", received[2]);
+        CollectionAssert.AreEqual(new byte[] { 1, 2 }, (byte[])received[3]!);
+    }
+
+    [TestMethod]
+    public void CreateScalarFunction_WhenFunctionThrows_ShouldFailStatementWithMessage()
+    {
+        // Arrange
+        _connection!.CreateScalarFunction("always_fails", 0, _ => throw new InvalidOperationException("boom"));
+
+        // Act
+        var exception = Assert.ThrowsExactly<TursoException>(() =>
+            _connection.QueryScalarInt64("SELECT always_fails()"));
+
+        // Assert
+        Assert.IsTrue(exception.Message.Contains("boom"));
+    }
+
+    [TestMethod]
+    public void CreateScalarFunction_OnTwoConnections_ShouldCallEachConnectionsFunction()
+    {
+        // Arrange
+        using var other = _database!.Connect();
+        _connection!.CreateScalarFunction("which_connection", 0, _ => "first");
+        other.CreateScalarFunction("which_connection", 0, _ => "second");
+
+        // Act & Assert
+        Assert.AreEqual("first", _connection.QueryScalarString("SELECT which_connection()"));
+        Assert.AreEqual("second", other.QueryScalarString("SELECT which_connection()"));
+    }
+
+    [TestMethod]
+    public void CreateScalarFunction_Again_ShouldReplaceFunction()
+    {
+        // Arrange
+        _connection!.CreateScalarFunction("version_of", 0, _ => 1L);
+
+        // Act
+        _connection.CreateScalarFunction("version_of", 0, _ => 2L);
+
+        // Assert
+        Assert.AreEqual(2L, _connection.QueryScalarInt64("SELECT version_of()"));
+    }
+
+    [TestMethod]
+    public void CreateScalarFunction_WhenConnectionIsDisposedBeforeStatement_ShouldStayCallable()
+    {
+        // Arrange
+        _connection!.CreateScalarFunction("double_it", 1, args => (long)args[0]! * 2);
+        using var statement = _connection.Prepare("SELECT double_it(21)");
+
+        // Act
+        _connection.Dispose();
+        GC.Collect();
+        GC.WaitForPendingFinalizers();
+
+        // Assert
+        Assert.AreEqual(1, statement.Step());
+        Assert.AreEqual(42L, statement.GetInt64(0));
+    }
+
+    [TestMethod]
+    public void CreateAggregateFunction_ShouldAggregateEachGroup()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE sales (region TEXT, amount INTEGER)");
+        _connection.Execute("INSERT INTO sales (region, amount) VALUES ('east', 10), ('west', 5), ('east', 20), ('west', 7)");
+        _connection.CreateAggregateFunction("sum_of_squares", 1, 0L,
+            (total, args) => total + (long)args[0]! * (long)args[0]!,
+            total => total);
+
+        // Act
+        var results = new Dictionary<string, long>();
+        using (var resultSet = _connection.Query("SELECT region, sum_of_squares(amount) FROM sales GROUP BY region"))
+        {
+            foreach (var row in resultSet)
+            {
+                results[row.GetString(0)!] = row.GetInt64(1);
+            }
+        }
+
+        // Assert
+        Assert.AreEqual(2, results.Count);
+        Assert.AreEqual(500L, results["east"]);
+        Assert.AreEqual(74L, results["west"]);
+    }
+
+    [TestMethod]
+    public void CreateAggregateFunction_WithNoRows_ShouldReturnSeedResult()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE words (word TEXT)");
+        _connection.CreateAggregateFunction("join_words", 1, string.Empty,
+            (words, args) => words.Length == 0 ? (string)args[0]! : words + " " + args[0],
+            words => words.Length == 0 ? "(none)" : words);
+
+        // Act
+        var empty = _connection.QueryScalarString("SELECT join_words(word) FROM words");
+        _connection.Execute("INSERT INTO words (word) VALUES ('hello'), ('world')");
+        var joined = _connection.QueryScalarString("SELECT join_words(word) FROM words");
+
+        // Assert
+        Assert.AreEqual("(none)", empty);
+        Assert.AreEqual("hello world", joined);
+    }
+
+    [TestMethod]
+    public void CreateAggregateFunction_WhenQueryIsDisposedEarly_ShouldFinishEveryStartedGroup()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE sales (region TEXT, amount INTEGER)");
+        _connection.Execute("INSERT INTO sales (region, amount) VALUES ('east', 1), ('north', 2), ('south', 3), ('west', 4)");
+        var started = 0;
+        var finished = 0;
+        _connection.CreateAggregateFunction("count_rows", 1, 0L,
+            (count, _) =>
+            {
+                if (count == 0)
+                    started++;
+                return count + 1;
+            },
+            count =>
+            {
+                finished++;
+                return count;
+            });
+
+        // Act
+        using (var resultSet = _connection.Query("SELECT region, count_rows(amount) FROM sales GROUP BY region"))
+        {
+            foreach (var _ in resultSet)
+            {
+                break;
+            }
+        }
+
+        // Assert
+        Assert.IsTrue(started > 0);
+        Assert.AreEqual(started, finished);
+    }
+}
@@ -38,6 +38,76 @@ public class TursoDatabaseTests
         Assert.ThrowsExactly<ArgumentException>(() => TursoDatabase.OpenFile(string.Empty));
     }
 
+    [TestMethod]
+    public void OpenFile_InMissingDirectory_ShouldIncludeCause()
+    {
+        var path = Path.Combine(Path.GetTempPath(), Guid.NewGuid().ToString(), "missing", "test.db");
+
+        var exception = Assert.ThrowsExactly<TursoException>(() => TursoDatabase.OpenFile(path));
+
+        Assert.IsTrue(exception.Message.StartsWith($"Failed to open database file {path}: "));
+        Assert.IsFalse(exception.Message.EndsWith("Unknown error"));
+        Assert.AreNotEqual(TursoErrorCode.Ok, exception.ErrorCode);
+    }
+
+    [TestMethod]
+    public void Open_WithReadOnly_ShouldRejectWrites()
+    {
+        var tempFile = Path.Combine(Path.GetTempPath(), $"{Guid.NewGuid()}.db");
+        try
+        {
+            using (var database = TursoDatabase.OpenFile(tempFile))
+            using (var connection = database.Connect())
+            {
+                connection.Execute("CREATE TABLE test (id INTEGER PRIMARY KEY)");
+                connection.Execute("INSERT INTO test (id) VALUES (1)");
+            }
+
+            using var readOnlyDatabase = TursoDatabase.Open(tempFile, new TursoDatabaseOptions { ReadOnly = true });
+            using var readOnlyConnection = readOnlyDatabase.Connect();
+
+            Assert.AreEqual(1L, readOnlyConnection.QueryScalarInt64("SELECT COUNT(*) FROM test"));
+            Assert.ThrowsExactly<TursoException>(() =>
+                readOnlyConnection.Execute("INSERT INTO test (id) VALUES (2)"));
+            Assert.AreEqual(1L, readOnlyConnection.QueryScalarInt64("SELECT COUNT(*) FROM test"));
+        }
+        finally
+        {
+            foreach (var file in new[] { tempFile, tempFile + "-wal" })
+            {
+                if (File.Exists(file))
+                    File.Delete(file);
+            }
+        }
+    }
+
+    [TestMethod]
+    public void Open_WithoutCreateIfMissing_ShouldFailForMissingFile()
+    {
+        var path = Path.Combine(Path.GetTempPath(), $"{Guid.NewGuid()}.db");
+
+        Assert.ThrowsExactly<TursoException>(() =>
+            TursoDatabase.Open(path, new TursoDatabaseOptions { CreateIfMissing = false }));
+        Assert.IsFalse(File.Exists(path));
+    }
+
+    [TestMethod]
+    public void Open_WithMemoryBackend_ShouldReportMemoryBackend()
+    {
+        using var database = TursoDatabase.Open(":memory:", new TursoDatabaseOptions { IoBackend = TursoIoBackend.Memory });
+
+        Assert.AreEqual(TursoIoBackend.Memory, database.IoBackend);
+    }
+
+    [TestMethod]
+    public void Open_WithInvalidBackend_ShouldThrowMisuse()
+    {
+        var exception = Assert.ThrowsExactly<TursoException>(() =>
+            TursoDatabase.Open(":memory:", new TursoDatabaseOptions { IoBackend = (TursoIoBackend)42 }));
+
+        Assert.AreEqual(TursoErrorCode.Misuse, exception.ErrorCode);
+    }
+
     [TestMethod]
     public void Connect_ShouldCreateConnection()
     {
@@ -49,6 +49,39 @@ public class TursoErrorHandlingTests
         Assert.IsTrue(exception.Message.Contains("Failed to execute SQL"));
     }
 
+    [TestMethod]
+    public void Execute_WithUniqueViolation_ShouldReportConstraintUnique()
+    {
+        _connection!.Execute("CREATE TABLE users (id INTEGER PRIMARY KEY, email TEXT UNIQUE)");
+        _connection.Execute("INSERT INTO users (id, email) VALUES (1, 'test@example.com')");
+
+        var exception = Assert.ThrowsExactly<TursoException>(() =>
+            _connection.Execute("INSERT INTO users (id, email) VALUES (2, 'test@example.com')"));
+
+        Assert.AreEqual(TursoErrorCode.Constraint, exception.ErrorCode);
+        Assert.AreEqual(TursoErrorCode.ConstraintUnique, exception.ExtendedErrorCode);
+        Assert.AreEqual(2067, (int)exception.ExtendedErrorCode);
+    }
+
+    [TestMethod]
+    public void Execute_WithSyntaxError_ShouldReportGenericError()
+    {
+        var exception = Assert.ThrowsExactly<TursoException>(() =>
+            _connection!.Execute("CREATE TABEL users (id INTEGER)"));
+
+        Assert.AreEqual(TursoErrorCode.Error, exception.ErrorCode);
+    }
+
+    [TestMethod]
+    public void Prepare_WithUnknownTable_ShouldReportErrorCode()
+    {
+        var exception = Assert.ThrowsExactly<TursoException>(() =>
+            _connection!.Prepare("SELECT * FROM nonexistent_table"));
+
+        Assert.AreNotEqual(TursoErrorCode.Ok, exception.ErrorCode);
+        Assert.AreEqual(exception.ErrorCode, (TursoErrorCode)((int)exception.ExtendedErrorCode & 0xff));
+    }
+
     [TestMethod]
     public void QueryScalarInt64_WithInvalidSql_ShouldThrowTursoException()
     {
@@ -71,9 +104,19 @@ public class TursoErrorHandlingTests
     }
 
     [TestMethod]
-    public void QueryScalarString_WithInvalidSql_ShouldReturnNull()
+    public void QueryScalarString_WithInvalidSql_ShouldThrowTursoException()
     {
-        var result = _connection!.QueryScalarString("INVALID SQL STATEMENT");
+        var exception = Assert.ThrowsExactly<TursoException>(() =>
+            _connection!.QueryScalarString("INVALID SQL STATEMENT"));
+
+        Assert.IsTrue(exception.Message.StartsWith("Failed to query scalar string: "));
+        Assert.IsFalse(exception.Message.EndsWith("Unknown error"));
+    }
+
+    [TestMethod]
+    public void QueryScalarString_WithNullValue_ShouldReturnNull()
+    {
+        var result = _connection!.QueryScalarString("SELECT NULL");
         Assert.IsNull(result);
     }
 
@@ -95,6 +138,56 @@ public class TursoErrorHandlingTests
         Assert.IsTrue(exception.Message.Contains("Failed to query scalar integer"));
     }
 
+    [TestMethod]
+    public void Prepare_WithSyntaxError_ShouldIncludeCause()
+    {
+        var exception = Assert.ThrowsExactly<TursoException>(() =>
+            _connection!.Prepare("SELEC * FROM users"));
+
+        Assert.IsTrue(exception.Message.StartsWith("Failed to prepare statement: "));
+        Assert.IsFalse(exception.Message.EndsWith("Unknown error"));
+    }
+
+    [TestMethod]
+    public void Read_WhenDatabaseIsLocked_ShouldReportBusyAndAllowRetry()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE test (id INTEGER PRIMARY KEY)");
+        using var writer = _database!.Connect();
+        writer.BeginTransaction(TursoTransactionBehavior.Immediate);
+
+        using var resultSet = _connection.Query("INSERT INTO test (id) VALUES (1)");
+
+        // Act
+        var exception = Assert.ThrowsExactly<TursoException>(() => resultSet.Read());
+        writer.CommitTransaction();
+
+        // Assert
+        Assert.AreEqual(TursoErrorCode.Busy, exception.ErrorCode);
+        Assert.IsTrue(exception.Message.StartsWith("Error stepping through result set: "));
+        Assert.IsFalse(exception.Message.EndsWith("Unknown error"));
+        Assert.IsFalse(resultSet.Read());
+        Assert.AreEqual(1L, _connection.QueryScalarInt64("SELECT COUNT(*) FROM test"));
+    }
+
+    [TestMethod]
+    public void Step_WhenDatabaseIsLocked_ShouldReturnBusy()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE test (id INTEGER PRIMARY KEY)");
+        using var writer = _database!.Connect();
+        writer.BeginTransaction(TursoTransactionBehavior.Immediate);
+
+        using var statement = _connection.Prepare("INSERT INTO test (id) VALUES (1)");
+
+        // Act
+        var status = statement.Step();
+        writer.RollbackTransaction();
+
+        // Assert
+        Assert.AreEqual(2, status);
+    }
+
     [TestMethod]
     public void QueryScalarString_WithUnicodeCharacters_ShouldWork()
     {
@@ -369,4 +369,71 @@ public class TursoStatementValueBindingTests
         Assert.AreEqual(TursoColumnType.Null, selectStmt.GetColumnType(0));
         Assert.IsTrue(selectStmt.IsNull(0), "Value should be null");
     }
-}
\ No newline at end of file
+
+    [TestMethod]
+    public void BindInt64Array_InJsonEachSubquery_ShouldMatchEachValue()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)");
+        _connection.Execute("INSERT INTO users (id, name) VALUES (1, 'ann'), (2, 'bob'), (3, 'cy'), (4, 'di')");
+        using var selectStmt = _connection.Prepare(
+            "SELECT name FROM users WHERE id IN (SELECT value FROM json_each(?)) ORDER BY id");
+
+        // Act
+        selectStmt.BindInt64Array(1, new long[] { 4, 2 });
+        var firstNames = new List<string?>();
+        while (selectStmt.Step() == 1)
+        {
+            firstNames.Add(selectStmt.GetString(0));
+        }
+
+        selectStmt.Reset();
+        selectStmt.BindInt64Array(1, new long[] { 1, 2, 3 });
+        var secondNames = new List<string?>();
+        while (selectStmt.Step() == 1)
+        {
+            secondNames.Add(selectStmt.GetString(0));
+        }
+
+        // Assert
+        CollectionAssert.AreEqual(new[] { "bob", "di" }, firstNames);
+        CollectionAssert.AreEqual(new[] { "ann", "bob", "cy" }, secondNames);
+    }
+
+    [TestMethod]
+    public void BindStringArray_InJsonEachSubquery_ShouldMatchEachValue()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT)");
+        _connection.Execute("INSERT INTO users (id, name) VALUES (1, 'ann'), (2, 'o\"brien'), (3, 'cy')");
+        using var selectStmt = _connection.Prepare(
+            "SELECT id FROM users WHERE name IN (SELECT value FROM json_each(?)) ORDER BY id");
+
+        // Act
+        selectStmt.BindStringArray(1, new[] { "cy", "o\"brien", null });
+        var ids = new List<long>();
+        while (selectStmt.Step() == 1)
+        {
+            ids.Add(selectStmt.GetInt64(0));
+        }
+
+        // Assert
+        CollectionAssert.AreEqual(new[] { 2L, 3L }, ids);
+    }
+
+    [TestMethod]
+    public void BindDoubleArray_ShouldBindRealElements()
+    {
+        // Arrange
+        using var selectStmt = _connection!.Prepare("SELECT SUM(value), MIN(type) FROM json_each(?)");
+
+        // Act
+        selectStmt.BindDoubleArray(1, new[] { 1.5, 2.0 });
+        var stepResult = selectStmt.Step();
+
+        // Assert
+        Assert.AreEqual(1, stepResult);
+        Assert.AreEqual(3.5, selectStmt.GetDouble(0));
+        Assert.AreEqual("real", selectStmt.GetString(1));
+    }
+}
@@ -1,3 +1,4 @@
+using System.Runtime.CompilerServices;
 using Microsoft.VisualStudio.TestTools.UnitTesting;
 
 namespace TursoSharp.Tests;
@@ -250,4 +251,286 @@ public class TursoTransactionTests
         var count = _connection.QueryScalarInt64("SELECT COUNT(*) FROM test_transactions");
         Assert.AreEqual(1L, count, "Transaction with default behavior should work");
     }
-}
\ No newline at end of file
+
+    [TestMethod]
+    public void StartTransaction_DisposedWithoutCommit_ShouldRollback()
+    {
+        // Act
+        using (var transaction = _connection!.StartTransaction())
+        {
+            _connection.Execute("INSERT INTO test_transactions (value) VALUES ('abandoned')");
+            Assert.IsFalse(_connection.IsAutoCommit, "Should be in a transaction");
+        }
+
+        // Assert
+        Assert.IsTrue(_connection.IsAutoCommit, "Disposing the transaction should end it");
+        var count = _connection.QueryScalarInt64("SELECT COUNT(*) FROM test_transactions");
+        Assert.AreEqual(0L, count, "Abandoned transaction should be rolled back");
+    }
+
+    [TestMethod]
+    public void StartTransaction_WithCommitDropBehavior_ShouldCommitOnDispose()
+    {
+        // Act
+        using (var transaction = _connection!.StartTransaction(dropBehavior: TursoTransactionDropBehavior.Commit))
+        {
+            _connection.Execute("INSERT INTO test_transactions (value) VALUES ('kept')");
+        }
+
+        // Assert
+        Assert.IsTrue(_connection.IsAutoCommit, "Disposing the transaction should end it");
+        var count = _connection.QueryScalarInt64("SELECT COUNT(*) FROM test_transactions");
+        Assert.AreEqual(1L, count, "Transaction should be committed on dispose");
+    }
+
+    [TestMethod]
+    public void StartTransaction_CommitThenDispose_ShouldKeepChanges()
+    {
+        // Act
+        using (var transaction = _connection!.StartTransaction())
+        {
+            _connection.Execute("INSERT INTO test_transactions (value) VALUES ('committed')");
+            transaction.Commit();
+        }
+
+        // Assert
+        var count = _connection.QueryScalarInt64("SELECT COUNT(*) FROM test_transactions");
+        Assert.AreEqual(1L, count, "Committed changes should survive dispose");
+    }
+
+    [TestMethod]
+    public void StartTransaction_WhenAlreadyInTransaction_ShouldThrowException()
+    {
+        // Arrange
+        _connection!.BeginTransaction();
+
+        // Act & Assert
+        Assert.ThrowsExactly<TursoException>(() => _connection.StartTransaction());
+
+        // Cleanup
+        _connection.RollbackTransaction();
+    }
+
+    [TestMethod]
+    public void StartTransaction_EndedThroughConnection_DisposeShouldNotTouchLaterTransaction()
+    {
+        // Arrange
+        var transaction = _connection!.StartTransaction();
+        _connection.Execute("INSERT INTO test_transactions (value) VALUES ('first')");
+        _connection.CommitTransaction();
+
+        _connection.BeginTransaction();
+        _connection.Execute("INSERT INTO test_transactions (value) VALUES ('second')");
+
+        // Act
+        transaction.Dispose();
+
+        // Assert
+        Assert.IsFalse(_connection.IsAutoCommit, "The later transaction should still be open");
+        _connection.CommitTransaction();
+        var count = _connection.QueryScalarInt64("SELECT COUNT(*) FROM test_transactions");
+        Assert.AreEqual(2L, count, "Disposing the stale handle should not roll back the later transaction");
+    }
+
+    [TestMethod]
+    public void StartTransaction_EndedWithSql_CommitShouldThrowWithoutCommittingLaterTransaction()
+    {
+        // Arrange
+        using var transaction = _connection!.StartTransaction();
+        _connection.Execute("INSERT INTO test_transactions (value) VALUES ('first')");
+        _connection.Execute("COMMIT");
+
+        _connection.Execute("BEGIN");
+        _connection.Execute("INSERT INTO test_transactions (value) VALUES ('second')");
+
+        // Act & Assert
+        Assert.ThrowsExactly<TursoException>(() => transaction.Commit());
+        Assert.IsFalse(_connection.IsAutoCommit, "The later transaction should still be open");
+
+        _connection.RollbackTransaction();
+        var count = _connection.QueryScalarInt64("SELECT COUNT(*) FROM test_transactions");
+        Assert.AreEqual(1L, count, "Only the first transaction should be committed");
+    }
+
+    [TestMethod]
+    public void StartTransaction_WithPanicDropBehavior_ShouldThrowFromDispose()
+    {
+        // Arrange
+        var transaction = _connection!.StartTransaction(dropBehavior: TursoTransactionDropBehavior.Panic);
+        _connection.Execute("INSERT INTO test_transactions (value) VALUES ('abandoned')");
+
+        // Act & Assert
+        Assert.ThrowsExactly<TursoException>(() => transaction.Dispose());
+        Assert.IsTrue(_connection.IsAutoCommit, "The transaction should be rolled back");
+        Assert.AreEqual(0L, _connection.QueryScalarInt64("SELECT COUNT(*) FROM test_transactions"));
+    }
+
+    [TestMethod]
+    public void StartTransaction_WithPanicDropBehavior_ShouldNotThrowFromFinalizer()
+    {
+        // Arrange
+        AbandonTransaction(_connection!);
+
+        // Act
+        GC.Collect();
+        GC.WaitForPendingFinalizers();
+
+        // Assert - reaching this point means the finalizer did not crash the process
+        Assert.IsTrue(_connection!.IsAutoCommit, "The abandoned transaction should be rolled back");
+        Assert.AreEqual(0L, _connection.QueryScalarInt64("SELECT COUNT(*) FROM test_transactions"));
+    }
+
+    [MethodImpl(MethodImplOptions.NoInlining)]
+    private static void AbandonTransaction(TursoConnection connection)
+    {
+        connection.StartTransaction(dropBehavior: TursoTransactionDropBehavior.Panic);
+        connection.Execute("INSERT INTO test_transactions (value) VALUES ('abandoned')");
+    }
+
+    [TestMethod]
+    public void BeginTransaction_WithDefaultBehaviorSet_ShouldUseDefault()
+    {
+        // Arrange
+        _connection!.SetDefaultTransactionBehavior(TursoTransactionBehavior.Immediate);
+        using var otherConnection = _database!.Connect();
+
+        // Act
+        _connection.BeginTransaction();
+
+        // Assert - IMMEDIATE takes the write lock at BEGIN, before this connection writes anything
+        var exception = Assert.ThrowsExactly<TursoException>(() =>
+            otherConnection.Execute("INSERT INTO test_transactions (value) VALUES ('other')"));
+        Assert.AreEqual(TursoErrorCode.Busy, exception.ErrorCode);
+
+        // Cleanup
+        _connection.RollbackTransaction();
+    }
+
+    [TestMethod]
+    public void BeginTransaction_WithoutDefaultBehaviorSet_ShouldNotLockUntilWrite()
+    {
+        // Arrange
+        using var otherConnection = _database!.Connect();
+
+        // Act
+        _connection!.BeginTransaction();
+
+        // Assert - DEFERRED takes no lock at BEGIN, so another connection can still write
+        otherConnection.Execute("INSERT INTO test_transactions (value) VALUES ('other')");
+        Assert.AreEqual(1L, otherConnection.QueryScalarInt64("SELECT COUNT(*) FROM test_transactions"));
+
+        // Cleanup
+        _connection.RollbackTransaction();
+    }
+
+    [TestMethod]
+    public void StartTransaction_WithInvalidDropBehavior_ShouldThrowMisuse()
+    {
+        var exception = Assert.ThrowsExactly<TursoException>(() =>
+            _connection!.StartTransaction(dropBehavior: (TursoTransactionDropBehavior)42));
+
+        Assert.AreEqual(TursoErrorCode.Misuse, exception.ErrorCode);
+        Assert.IsTrue(_connection!.IsAutoCommit);
+    }
+
+    [TestMethod]
+    public void SetDefaultTransactionBehavior_WithInvalidBehavior_ShouldThrowMisuse()
+    {
+        var exception = Assert.ThrowsExactly<TursoException>(() =>
+            _connection!.SetDefaultTransactionBehavior((TursoTransactionBehavior)42));
+
+        Assert.AreEqual(TursoErrorCode.Misuse, exception.ErrorCode);
+    }
+
+    [TestMethod]
+    public void SetCommitHook_WhenHookVetoes_ShouldRollBackAndFailCommit()
+    {
+        // Arrange
+        var rollbacks = 0;
+        var postCommits = 0;
+        _connection!.SetCommitHook(() => false);
+        _connection.SetRollbackHook(() => rollbacks++);
+        _connection.SetPostCommitHook(() => postCommits++);
+        _connection.BeginTransaction();
+        _connection.Execute("INSERT INTO test_transactions (value) VALUES ('vetoed')");
+
+        // Act
+        var exception = Assert.ThrowsExactly<TursoException>(() => _connection.CommitTransaction());
+
+        // Assert
+        Assert.AreEqual(TursoErrorCode.Constraint, exception.ErrorCode);
+        Assert.AreEqual(TursoErrorCode.ConstraintCommitHook, exception.ExtendedErrorCode);
+        Assert.IsTrue(_connection.IsAutoCommit);
+        Assert.AreEqual(0L, _connection.QueryScalarInt64("SELECT COUNT(*) FROM test_transactions"));
+        Assert.AreEqual(1, rollbacks);
+        Assert.AreEqual(0, postCommits);
+    }
+
+    [TestMethod]
+    public void SetRollbackHook_ShouldFireForEveryRolledBackTransaction()
+    {
+        // Arrange
+        var rollbacks = 0;
+        _connection!.SetRollbackHook(() => rollbacks++);
+
+        // Act
+        _connection.BeginTransaction();
+        _connection.Execute("INSERT INTO test_transactions (value) VALUES ('a')");
+        _connection.RollbackTransaction();
+
+        _connection.Execute("BEGIN");
+        _connection.Execute("INSERT INTO test_transactions (value) VALUES ('b')");
+        _connection.Execute("ROLLBACK");
+
+        using (_connection.StartTransaction())
+        {
+            _connection.Execute("INSERT INTO test_transactions (value) VALUES ('c')");
+        }
+
+        // Assert
+        Assert.AreEqual(3, rollbacks);
+        Assert.AreEqual(0L, _connection.QueryScalarInt64("SELECT COUNT(*) FROM test_transactions"));
+    }
+
+    [TestMethod]
+    public void SetCommitHook_ShouldFireForWritesInAutocommitMode()
+    {
+        // Arrange
+        var commits = 0;
+        var postCommits = 0;
+        _connection!.SetCommitHook(() =>
+        {
+            commits++;
+            return true;
+        });
+        _connection.SetPostCommitHook(() => postCommits++);
+
+        // Act
+        _connection.Execute("INSERT INTO test_transactions (value) VALUES ('a')");
+        _connection.Execute("WITH v(x) AS (VALUES ('b')) INSERT INTO test_transactions (value) SELECT x FROM v");
+        _connection.QueryScalarInt64("SELECT COUNT(*) FROM test_transactions");
+
+        // Assert
+        Assert.AreEqual(2, commits);
+        Assert.AreEqual(2, postCommits);
+        Assert.AreEqual(2L, _connection.QueryScalarInt64("SELECT COUNT(*) FROM test_transactions"));
+    }
+
+    [TestMethod]
+    public void SetCommitHook_WithoutOpenTransaction_ShouldNotFire()
+    {
+        // Arrange
+        var commits = 0;
+        _connection!.SetCommitHook(() =>
+        {
+            commits++;
+            return true;
+        });
+
+        // Act
+        Assert.ThrowsExactly<TursoException>(() => _connection.Execute("COMMIT"));
+
+        // Assert
+        Assert.AreEqual(0, commits);
+    }
+}
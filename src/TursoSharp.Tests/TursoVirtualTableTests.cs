@@ -0,0 +1,156 @@
+namespace TursoSharp.Tests;
+
+[TestClass]
+public class TursoVirtualTableTests
+{
+    private TursoDatabase? _database;
+    private TursoConnection? _connection;
+
+    [TestInitialize]
+    public void Setup()
+    {
+        _database = TursoDatabase.OpenMemory();
+        _connection = _database.Connect();
+    }
+
+    [TestCleanup]
+    public void Cleanup()
+    {
+        _connection?.Dispose();
+        _database?.Dispose();
+    }
+
+    [TestMethod]
+    public void CreateModule_ShouldServeRowsThroughQueries()
+    {
+        // Arrange
+        var module = new NumbersModule();
+        _connection!.CreateModule("numbers", module);
+        _connection.Execute("CREATE VIRTUAL TABLE n USING numbers(5)");
+
+        // Act
+        var rows = new List<(long Value, long Square, long RowId)>();
+        using (var resultSet = _connection.Query("SELECT value, square, rowid FROM n"))
+        {
+            foreach (var row in resultSet)
+            {
+                rows.Add((row.GetInt64(0), row.GetInt64(1), row.GetInt64(2)));
+            }
+        }
+
+        // Assert
+        CollectionAssert.AreEqual(new[] { "5" }, module.Arguments);
+        CollectionAssert.AreEqual(
+            new[] { (1L, 1L, 1L), (2L, 4L, 2L), (3L, 9L, 3L), (4L, 16L, 4L), (5L, 25L, 5L) },
+            rows);
+        Assert.AreEqual(15L, _connection.QueryScalarInt64("SELECT SUM(value) FROM n"));
+    }
+
+    [TestMethod]
+    public void CreateModule_WithBestIndex_ShouldPassConstraintValuesToFilter()
+    {
+        // Arrange
+        var module = new NumbersModule();
+        _connection!.CreateModule("numbers", module);
+        _connection.Execute("CREATE VIRTUAL TABLE n USING numbers(10)");
+
+        // Act
+        var square = _connection.QueryScalarInt64("SELECT square FROM n WHERE value = 3");
+
+        // Assert
+        Assert.AreEqual(9L, square);
+        Assert.AreEqual(NumbersModule.ValueEquals, module.LastIndexNumber);
+        CollectionAssert.AreEqual(new object?[] { 3L }, module.LastFilterArguments);
+    }
+
+    [TestMethod]
+    public void CreateModule_WhenColumnThrows_ShouldFailStatementWithMessage()
+    {
+        // Arrange
+        _connection!.CreateModule("numbers", new NumbersModule { FailColumns = true });
+        _connection.Execute("CREATE VIRTUAL TABLE n USING numbers(3)");
+
+        // Act
+        var exception = Assert.ThrowsExactly<TursoException>(() =>
+            _connection.QueryScalarInt64("SELECT value FROM n"));
+
+        // Assert
+        Assert.IsTrue(exception.Message.Contains("no columns today"));
+    }
+
+    // Rows 1..count with their squares, planning `value = ?` as a single-row lookup
+    private sealed class NumbersModule : TursoVirtualTableModule
+    {
+        public const int ValueEquals = 1;
+
+        public string[]? Arguments { get; private set; }
+
+        public int? LastIndexNumber { get; set; }
+
+        public object?[]? LastFilterArguments { get; set; }
+
+        public bool FailColumns { get; init; }
+
+        public override TursoVirtualTable Connect(IReadOnlyList<string> arguments)
+        {
+            Arguments = arguments.ToArray();
+            return new NumbersTable(this, long.Parse(arguments[0]));
+        }
+
+        public override TursoVirtualTablePlan? BestIndex(IReadOnlyList<TursoVirtualTableConstraint> constraints)
+        {
+            foreach (var constraint in constraints)
+            {
+                if (constraint is { Column: 0, Operator: 2, Usable: true })
+                {
+                    constraint.ArgumentIndex = 1;
+                    constraint.Omit = true;
+                    return new TursoVirtualTablePlan(ValueEquals, 1, 1);
+                }
+            }
+            return null;
+        }
+    }
+
+    private sealed class NumbersTable(NumbersModule module, long count) : TursoVirtualTable
+    {
+        public override string Schema => "CREATE TABLE x (value INTEGER, square INTEGER)";
+
+        public override TursoVirtualTableCursor Open() => new NumbersCursor(module, count);
+    }
+
+    private sealed class NumbersCursor(NumbersModule module, long count) : TursoVirtualTableCursor
+    {
+        private long _current;
+        private long _last;
+
+        public override void Filter(int indexNumber, object?[] arguments)
+        {
+            module.LastIndexNumber = indexNumber;
+            module.LastFilterArguments = arguments;
+            if (indexNumber == NumbersModule.ValueEquals)
+            {
+                _current = (long)arguments[0]!;
+                _last = Math.Min(_current, count);
+            }
+            else
+            {
+                _current = 1;
+                _last = count;
+            }
+        }
+
+        public override void Next() => _current++;
+
+        public override bool Eof => _current < 1 || _current > _last;
+
+        public override object? Column(int column)
+        {
+            if (module.FailColumns)
+                throw new InvalidOperationException("no columns today");
+            return column == 0 ? _current : _current * _current;
+        }
+
+        public override long RowId => _current;
+    }
+}
@@ -0,0 +1,181 @@
+namespace TursoSharp.Tests;
+
+[TestClass]
+public class TursoWalTests
+{
+    private string? _path;
+    private TursoDatabase? _database;
+    private TursoConnection? _connection;
+
+    [TestInitialize]
+    public void Setup()
+    {
+        _path = Path.Combine(Path.GetTempPath(), $"{Guid.NewGuid()}.db");
+        _database = TursoDatabase.OpenFile(_path);
+        _connection = _database.Connect();
+    }
+
+    [TestCleanup]
+    public void Cleanup()
+    {
+        _connection?.Dispose();
+        _database?.Dispose();
+        DeleteDatabaseFiles(_path!);
+    }
+
+    [TestMethod]
+    public void Checkpoint_AfterWrites_ShouldCheckpointEveryWalFrame()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE test (id INTEGER PRIMARY KEY, value TEXT)");
+        _connection.Execute("INSERT INTO test (value) VALUES ('a'), ('b'), ('c')");
+
+        // Act
+        var checkpoint = _connection.Checkpoint(TursoCheckpointMode.Full);
+
+        // Assert
+        Assert.IsTrue(checkpoint.WalFrames > 0);
+        Assert.AreEqual(checkpoint.WalFrames, checkpoint.CheckpointedFrames);
+        Assert.AreEqual(3L, _connection.QueryScalarInt64("SELECT COUNT(*) FROM test"));
+    }
+
+    [TestMethod]
+    public void Checkpoint_WithTruncate_ShouldEmptyTheWal()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE test (id INTEGER PRIMARY KEY)");
+        _connection.Execute("INSERT INTO test (id) VALUES (1)");
+
+        // Act
+        _connection.Checkpoint(TursoCheckpointMode.Truncate);
+        var afterTruncate = _connection.Checkpoint(TursoCheckpointMode.Passive);
+
+        // Assert
+        Assert.AreEqual(0L, afterTruncate.WalFrames);
+        Assert.AreEqual(1L, _connection.QueryScalarInt64("SELECT COUNT(*) FROM test"));
+    }
+
+    [TestMethod]
+    public void Checkpoint_WithInvalidMode_ShouldThrowMisuse()
+    {
+        var exception = Assert.ThrowsExactly<TursoException>(() => _connection!.Checkpoint((TursoCheckpointMode)42));
+
+        Assert.AreEqual(TursoErrorCode.Misuse, exception.ErrorCode);
+    }
+
+    [TestMethod]
+    public void StartAutoCheckpoint_WhenThresholdIsPassed_ShouldTruncateTheWal()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE test (id INTEGER PRIMARY KEY, value TEXT)");
+        var walFile = new FileInfo(_path + "-wal");
+        var errors = new List<TursoException>();
+
+        // Act
+        _database!.StartAutoCheckpoint(
+            new TursoAutoCheckpointOptions
+            {
+                WalFrameThreshold = 1,
+                PollInterval = TimeSpan.FromMilliseconds(10),
+                Mode = TursoCheckpointMode.Truncate
+            },
+            error => { lock (errors) errors.Add(error); });
+        _connection.Execute("INSERT INTO test (value) VALUES ('a'), ('b')");
+
+        var deadline = DateTime.UtcNow.AddSeconds(10);
+        do
+        {
+            Thread.Sleep(20);
+            walFile.Refresh();
+        } while (walFile.Exists && walFile.Length > 0 && DateTime.UtcNow < deadline);
+        _database.StopAutoCheckpoint();
+
+        // Assert
+        Assert.AreEqual(0L, walFile.Exists ? walFile.Length : 0L);
+        Assert.AreEqual(0, errors.Count);
+        Assert.AreEqual(2L, _connection.QueryScalarInt64("SELECT COUNT(*) FROM test"));
+    }
+
+    [TestMethod]
+    public void StopAutoCheckpoint_ShouldLeaveNewFramesInTheWal()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE test (id INTEGER PRIMARY KEY)");
+        _database!.StartAutoCheckpoint(new TursoAutoCheckpointOptions
+        {
+            WalFrameThreshold = 1,
+            PollInterval = TimeSpan.FromMilliseconds(10),
+            Mode = TursoCheckpointMode.Truncate
+        });
+
+        // Act
+        _database.StopAutoCheckpoint();
+        _connection.Execute("INSERT INTO test (id) VALUES (1)");
+        Thread.Sleep(100);
+
+        // Assert
+        Assert.IsTrue(_connection.Checkpoint(TursoCheckpointMode.Passive).WalFrames > 0);
+    }
+
+    [TestMethod]
+    public void StartAutoCheckpoint_WithoutLimits_ShouldThrow()
+    {
+        Assert.ThrowsExactly<TursoException>(() =>
+            _database!.StartAutoCheckpoint(new TursoAutoCheckpointOptions()));
+    }
+
+    [TestMethod]
+    public void InsertWalFrame_WithFramesFromSource_ShouldReplicateTheData()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE test (id INTEGER PRIMARY KEY, value TEXT)");
+        _connection.Execute("INSERT INTO test (value) VALUES ('a'), ('b'), ('c')");
+        var frameCount = _connection.WalFrameCount;
+        var frame = new byte[_connection.QueryScalarInt64("PRAGMA page_size") + 24];
+
+        var replicaPath = Path.Combine(Path.GetTempPath(), $"{Guid.NewGuid()}.db");
+        try
+        {
+            using var replica = TursoDatabase.OpenFile(replicaPath);
+            using var replicaConnection = replica.Connect();
+
+            // Act
+            replicaConnection.BeginWalInsert();
+            for (long frameNumber = 1; frameNumber <= frameCount; frameNumber++)
+            {
+                _connection.GetWalFrame(frameNumber, frame);
+                replicaConnection.InsertWalFrame(frameNumber, frame);
+            }
+            replicaConnection.EndWalInsert();
+
+            // Assert
+            Assert.IsTrue(frameCount > 0);
+            Assert.AreEqual(frameCount, replicaConnection.WalFrameCount);
+            Assert.AreEqual(3L, replicaConnection.QueryScalarInt64("SELECT COUNT(*) FROM test"));
+            Assert.AreEqual("c", replicaConnection.QueryScalarString("SELECT value FROM test WHERE id = 3"));
+        }
+        finally
+        {
+            DeleteDatabaseFiles(replicaPath);
+        }
+    }
+
+    [TestMethod]
+    public void GetWalFrame_WithWrongBufferSize_ShouldThrow()
+    {
+        // Arrange
+        _connection!.Execute("CREATE TABLE test (id INTEGER PRIMARY KEY)");
+
+        // Act & Assert
+        Assert.ThrowsExactly<TursoException>(() => _connection.GetWalFrame(1, new byte[16]));
+    }
+
+    private static void DeleteDatabaseFiles(string path)
+    {
+        foreach (var file in new[] { path, path + "-wal" })
+        {
+            if (File.Exists(file))
+                File.Delete(file);
+        }
+    }
+}
@@ -29,9 +29,15 @@ namespace Turso.Native
         [DllImport(__DllName, EntryPoint = "turso_database_open_file", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern void* turso_database_open_file(byte* path);
 
+        [DllImport(__DllName, EntryPoint = "turso_database_open_with_options", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern void* turso_database_open_with_options(byte* path, TursoOpenOptions* options);
+
         [DllImport(__DllName, EntryPoint = "turso_database_close", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern TursoFFIResult turso_database_close(void* database_ptr);
 
+        [DllImport(__DllName, EntryPoint = "turso_database_get_io_backend", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_database_get_io_backend(void* database_ptr, TursoIoBackend* result);
+
         [DllImport(__DllName, EntryPoint = "turso_connection_open", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern void* turso_connection_open(void* database_ptr);
 
@@ -41,6 +47,9 @@ namespace Turso.Native
         [DllImport(__DllName, EntryPoint = "turso_connection_execute", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern TursoFFIResult turso_connection_execute(void* connection_ptr, byte* sql, ulong* rows_changed);
 
+        [DllImport(__DllName, EntryPoint = "turso_connection_execute_batch", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_execute_batch(void* connection_ptr, byte* sql, [MarshalAs(UnmanagedType.U1)] bool transactional, int* failed_statement_index, ulong* rows_changed);
+
         [DllImport(__DllName, EntryPoint = "turso_connection_query", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern void* turso_connection_query(void* connection_ptr, byte* sql);
 
@@ -54,13 +63,13 @@ namespace Turso.Native
         internal static extern void* turso_connection_prepare(void* connection_ptr, byte* sql);
 
         [DllImport(__DllName, EntryPoint = "turso_rows_next", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
-        internal static extern int turso_rows_next(void* rows_ptr);
+        internal static extern TursoStepStatus turso_rows_next(void* rows_ptr);
 
         [DllImport(__DllName, EntryPoint = "turso_rows_close", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern TursoFFIResult turso_rows_close(void* rows_ptr);
 
         [DllImport(__DllName, EntryPoint = "turso_statement_step", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
-        internal static extern int turso_statement_step(void* statement_ptr);
+        internal static extern TursoStepStatus turso_statement_step(void* statement_ptr);
 
         [DllImport(__DllName, EntryPoint = "turso_rows_column_count", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern int turso_rows_column_count(void* rows_ptr);
@@ -118,9 +127,24 @@ namespace Turso.Native
         [DllImport(__DllName, EntryPoint = "turso_statement_bind_text", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern TursoFFIResult turso_statement_bind_text(void* statement_ptr, int param_index, byte* value);
 
+        [DllImport(__DllName, EntryPoint = "turso_statement_bind_int64_array", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_statement_bind_int64_array(void* statement_ptr, int param_index, long* values, int count);
+
+        [DllImport(__DllName, EntryPoint = "turso_statement_bind_double_array", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_statement_bind_double_array(void* statement_ptr, int param_index, double* values, int count);
+
+        [DllImport(__DllName, EntryPoint = "turso_statement_bind_text_array", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_statement_bind_text_array(void* statement_ptr, int param_index, byte** values, int count);
+
         [DllImport(__DllName, EntryPoint = "turso_statement_reset", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern TursoFFIResult turso_statement_reset(void* statement_ptr);
 
+        [DllImport(__DllName, EntryPoint = "turso_statement_changes", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_statement_changes(void* statement_ptr, long* result);
+
+        [DllImport(__DllName, EntryPoint = "turso_statement_last_insert_rowid", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_statement_last_insert_rowid(void* statement_ptr, long* result);
+
         [DllImport(__DllName, EntryPoint = "turso_statement_bind_blob", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern TursoFFIResult turso_statement_bind_blob(void* statement_ptr, int param_index, byte* data, int data_len);
 
@@ -136,6 +160,9 @@ namespace Turso.Native
         [DllImport(__DllName, EntryPoint = "turso_statement_bind_null", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern TursoFFIResult turso_statement_bind_null(void* statement_ptr, int param_index);
 
+        [DllImport(__DllName, EntryPoint = "turso_connection_set_default_transaction_behavior", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_set_default_transaction_behavior(void* connection_ptr, int behavior);
+
         [DllImport(__DllName, EntryPoint = "turso_connection_begin_transaction", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern TursoFFIResult turso_connection_begin_transaction(void* connection_ptr, int behavior);
 
@@ -145,9 +172,135 @@ namespace Turso.Native
         [DllImport(__DllName, EntryPoint = "turso_connection_rollback_transaction", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern TursoFFIResult turso_connection_rollback_transaction(void* connection_ptr);
 
+        [DllImport(__DllName, EntryPoint = "turso_transaction_begin", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern void* turso_transaction_begin(void* connection_ptr, int behavior, int drop_behavior);
+
+        [DllImport(__DllName, EntryPoint = "turso_transaction_commit", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_transaction_commit(void* transaction_ptr);
+
+        [DllImport(__DllName, EntryPoint = "turso_transaction_rollback", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_transaction_rollback(void* transaction_ptr);
+
+        [DllImport(__DllName, EntryPoint = "turso_transaction_finalize", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_transaction_finalize(void* transaction_ptr);
+
         [DllImport(__DllName, EntryPoint = "turso_connection_is_autocommit", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern TursoFFIResult turso_connection_is_autocommit(void* connection_ptr, bool* result);
 
+        [DllImport(__DllName, EntryPoint = "turso_connection_last_insert_rowid", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_last_insert_rowid(void* connection_ptr, long* result);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_changes", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_changes(void* connection_ptr, long* result);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_total_changes", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_total_changes(void* connection_ptr, long* result);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_interrupt", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_interrupt(void* connection_ptr);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_set_busy_timeout", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_set_busy_timeout(void* connection_ptr, int timeout_ms);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_set_busy_handler", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_set_busy_handler(void* connection_ptr, delegate* unmanaged[Cdecl]<void*, int, int> handler, void* user_data);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_checkpoint", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_checkpoint(void* connection_ptr, int mode, long* wal_frames, long* checkpointed_frames);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_wal_frame_count", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_wal_frame_count(void* connection_ptr, ulong* result);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_wal_get_frame", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_wal_get_frame(void* connection_ptr, ulong frame_no, byte* buf, uint buf_len);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_wal_insert_begin", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_wal_insert_begin(void* connection_ptr);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_wal_insert_frame", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_wal_insert_frame(void* connection_ptr, ulong frame_no, byte* data, uint data_len);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_wal_insert_end", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_wal_insert_end(void* connection_ptr);
+
+        [DllImport(__DllName, EntryPoint = "turso_database_start_auto_checkpoint", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_database_start_auto_checkpoint(void* database_ptr, TursoAutoCheckpointOptions* options, delegate* unmanaged[Cdecl]<void*, TursoErrorCode, byte*, void> error_callback, void* user_data);
+
+        [DllImport(__DllName, EntryPoint = "turso_database_stop_auto_checkpoint", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_database_stop_auto_checkpoint(void* database_ptr);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_enable_load_extension", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_enable_load_extension(void* connection_ptr, [MarshalAs(UnmanagedType.U1)] bool enabled);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_load_extension", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_load_extension(void* connection_ptr, byte* path, byte* entry_point);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_set_commit_hook", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_set_commit_hook(void* connection_ptr, delegate* unmanaged[Cdecl]<void*, int> hook, void* user_data);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_set_post_commit_hook", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_set_post_commit_hook(void* connection_ptr, delegate* unmanaged[Cdecl]<void*, void> hook, void* user_data);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_set_rollback_hook", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_set_rollback_hook(void* connection_ptr, delegate* unmanaged[Cdecl]<void*, void> hook, void* user_data);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_create_scalar_function", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_create_scalar_function(void* connection_ptr, byte* name, int n_args, [MarshalAs(UnmanagedType.U1)] bool deterministic, delegate* unmanaged[Cdecl]<void*, void*, int, void**, void> callback, void* user_data);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_create_aggregate_function", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_create_aggregate_function(void* connection_ptr, byte* name, int n_args, delegate* unmanaged[Cdecl]<void*, void*, int, void**, void> step, delegate* unmanaged[Cdecl]<void*, void*, void> final_callback, delegate* unmanaged[Cdecl]<void*, void*, int, void**, void> inverse, delegate* unmanaged[Cdecl]<void*, void*, void> value, void* user_data);
+
+        [DllImport(__DllName, EntryPoint = "turso_aggregate_context", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern void* turso_aggregate_context(void* context_ptr, int n_bytes);
+
+        [DllImport(__DllName, EntryPoint = "turso_value_type", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern int turso_value_type(void* value_ptr);
+
+        [DllImport(__DllName, EntryPoint = "turso_value_int64", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern long turso_value_int64(void* value_ptr);
+
+        [DllImport(__DllName, EntryPoint = "turso_value_double", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern double turso_value_double(void* value_ptr);
+
+        [DllImport(__DllName, EntryPoint = "turso_value_text", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern byte* turso_value_text(void* value_ptr);
+
+        [DllImport(__DllName, EntryPoint = "turso_value_blob", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern byte* turso_value_blob(void* value_ptr, int* data_len);
+
+        [DllImport(__DllName, EntryPoint = "turso_result_null", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_result_null(void* context_ptr);
+
+        [DllImport(__DllName, EntryPoint = "turso_result_int64", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_result_int64(void* context_ptr, long value);
+
+        [DllImport(__DllName, EntryPoint = "turso_result_double", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_result_double(void* context_ptr, double value);
+
+        [DllImport(__DllName, EntryPoint = "turso_result_text", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_result_text(void* context_ptr, byte* value);
+
+        [DllImport(__DllName, EntryPoint = "turso_result_blob", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_result_blob(void* context_ptr, byte* data, int data_len);
+
+        [DllImport(__DllName, EntryPoint = "turso_result_error", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_result_error(void* context_ptr, byte* message);
+
+        [DllImport(__DllName, EntryPoint = "turso_connection_create_module", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_connection_create_module(void* connection_ptr, byte* name, TursoVTabModule* module, void* user_data);
+
+        [DllImport(__DllName, EntryPoint = "turso_vtab_declare", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoFFIResult turso_vtab_declare(void* context_ptr, byte* sql);
+
+        [DllImport(__DllName, EntryPoint = "turso_last_error_code", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoErrorCode turso_last_error_code();
+
+        [DllImport(__DllName, EntryPoint = "turso_last_error_extended_code", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern TursoErrorCode turso_last_error_extended_code();
+
+        [DllImport(__DllName, EntryPoint = "turso_last_error_message", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
+        internal static extern byte* turso_last_error_message();
+
         [DllImport(__DllName, EntryPoint = "turso_free_string", CallingConvention = CallingConvention.Cdecl, ExactSpelling = true)]
         internal static extern void turso_free_string(byte* ptr);
 
@@ -162,8 +315,115 @@ namespace Turso.Native
     {
         [MarshalAs(UnmanagedType.U1)] public bool success;
         public byte* error_message;
+        public TursoErrorCode error_code;
+        public TursoErrorCode extended_error_code;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct TursoOpenOptions
+    {
+        [MarshalAs(UnmanagedType.U1)] public bool read_only;
+        [MarshalAs(UnmanagedType.U1)] public bool create_if_missing;
+        [MarshalAs(UnmanagedType.U1)] public bool enable_mvcc;
+        [MarshalAs(UnmanagedType.U1)] public bool enable_indexes;
+        public int io_backend;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct TursoAutoCheckpointOptions
+    {
+        public ulong wal_frame_threshold;
+        public ulong max_wal_age_ms;
+        public ulong poll_interval_ms;
+        public int mode;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct TursoVTabModule
+    {
+        public delegate* unmanaged[Cdecl]<void*, void*, int, void**, void*> connect;
+        public delegate* unmanaged[Cdecl]<void*, TursoIndexConstraint*, int, TursoIndexPlan*, int> best_index;
+        public delegate* unmanaged[Cdecl]<void*, void> disconnect;
+        public delegate* unmanaged[Cdecl]<void*, void*> open;
+        public delegate* unmanaged[Cdecl]<void*, void> close;
+        public delegate* unmanaged[Cdecl]<void*, int, int, void**, int> filter;
+        public delegate* unmanaged[Cdecl]<void*, int> next;
+        public delegate* unmanaged[Cdecl]<void*, bool> eof;
+        public delegate* unmanaged[Cdecl]<void*, void*, int, void> column;
+        public delegate* unmanaged[Cdecl]<void*, long> rowid;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct TursoIndexConstraint
+    {
+        public int column;
+        public int op;
+        [MarshalAs(UnmanagedType.U1)] public bool usable;
+        public int argv_index;
+        [MarshalAs(UnmanagedType.U1)] public bool omit;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal unsafe partial struct TursoIndexPlan
+    {
+        public int idx_num;
+        public double estimated_cost;
+        public long estimated_rows;
+    }
+
+
+    internal enum TursoErrorCode : int
+    {
+        Ok = 0,
+        Error = 1,
+        Internal = 2,
+        Perm = 3,
+        Abort = 4,
+        Busy = 5,
+        Locked = 6,
+        NoMem = 7,
+        ReadOnly = 8,
+        Interrupt = 9,
+        IoErr = 10,
+        Corrupt = 11,
+        NotFound = 12,
+        Full = 13,
+        CantOpen = 14,
+        Schema = 17,
+        TooBig = 18,
+        Constraint = 19,
+        Mismatch = 20,
+        Misuse = 21,
+        Range = 25,
+        NotADb = 26,
+        IoErrRead = 266,
+        IoErrWrite = 778,
+        IoErrLock = 3850,
+        ConstraintCheck = 275,
+        ConstraintForeignKey = 787,
+        ConstraintNotNull = 1299,
+        ConstraintPrimaryKey = 1555,
+        ConstraintUnique = 2067,
+        ConstraintCommitHook = 531,
     }
 
+    internal enum TursoStepStatus : int
+    {
+        Error = -1,
+        Done = 0,
+        Row = 1,
+        Busy = 2,
+        Interrupted = 3,
+        Misuse = 4,
+    }
+
+    internal enum TursoIoBackend : int
+    {
+        Default = 0,
+        Memory = 1,
+        Syscall = 2,
+        IoUring = 3,
+    }
 
 
 }
@@ -0,0 +1,28 @@
+namespace TursoSharp;
+
+/// <summary>
+/// Options for <see cref="TursoDatabase.StartAutoCheckpoint"/>. A checkpoint runs when either
+/// limit is reached, so at least one of them must be set.
+/// </summary>
+public sealed class TursoAutoCheckpointOptions
+{
+    /// <summary>
+    /// Checkpoint once this many WAL frames are waiting to be copied. Defaults to 0, which disables the limit.
+    /// </summary>
+    public long WalFrameThreshold { get; init; }
+
+    /// <summary>
+    /// Checkpoint once WAL frames have been waiting this long. Defaults to null, which disables the limit.
+    /// </summary>
+    public TimeSpan? MaxWalAge { get; init; }
+
+    /// <summary>
+    /// How often the WAL is inspected. Defaults to one second.
+    /// </summary>
+    public TimeSpan PollInterval { get; init; } = TimeSpan.FromSeconds(1);
+
+    /// <summary>
+    /// Checkpoint mode to use. Defaults to <see cref="TursoCheckpointMode.Passive"/>.
+    /// </summary>
+    public TursoCheckpointMode Mode { get; init; } = TursoCheckpointMode.Passive;
+}
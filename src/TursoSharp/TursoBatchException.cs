@@ -0,0 +1,31 @@
+namespace TursoSharp;
+
+/// <summary>
+/// Exception thrown when a statement of a batch run by <see cref="TursoConnection.ExecuteBatch"/> fails
+/// </summary>
+public class TursoBatchException : TursoException
+{
+    public TursoBatchException(
+        string message,
+        TursoErrorCode errorCode,
+        TursoErrorCode extendedErrorCode,
+        int failedStatementIndex,
+        long rowsChanged)
+        : base(message, errorCode, extendedErrorCode)
+    {
+        FailedStatementIndex = failedStatementIndex;
+        RowsChanged = rowsChanged;
+    }
+
+    /// <summary>
+    /// Zero-based index of the statement that failed, or -1 if the failure was not tied to a
+    /// statement, e.g. when committing a transactional batch
+    /// </summary>
+    public int FailedStatementIndex { get; }
+
+    /// <summary>
+    /// Rows changed by the statements that ran before the failure. Always 0 for a transactional
+    /// batch, whose changes are rolled back.
+    /// </summary>
+    public long RowsChanged { get; }
+}
@@ -0,0 +1,27 @@
+namespace TursoSharp;
+
+/// <summary>
+/// How a checkpoint copies the WAL into the database file
+/// </summary>
+public enum TursoCheckpointMode
+{
+    /// <summary>
+    /// Checkpoint as many frames as possible without waiting for other connections
+    /// </summary>
+    Passive = 0,
+
+    /// <summary>
+    /// Wait for writers, then checkpoint every frame
+    /// </summary>
+    Full = 1,
+
+    /// <summary>
+    /// Like <see cref="Full"/>, and also wait for readers so the WAL starts over
+    /// </summary>
+    Restart = 2,
+
+    /// <summary>
+    /// Like <see cref="Restart"/>, and also truncate the WAL file
+    /// </summary>
+    Truncate = 3
+}
@@ -0,0 +1,8 @@
+namespace TursoSharp;
+
+/// <summary>
+/// Outcome of a checkpoint
+/// </summary>
+/// <param name="WalFrames">Number of frames in the WAL</param>
+/// <param name="CheckpointedFrames">Number of frames copied into the database file</param>
+public readonly record struct TursoCheckpointResult(long WalFrames, long CheckpointedFrames);
@@ -1,4 +1,5 @@
 using System;
+using System.Runtime.CompilerServices;
 using System.Runtime.InteropServices;
 using System.Text;
 using Turso.Native;
@@ -13,6 +14,22 @@ public sealed unsafe class TursoConnection : IDisposable
     private void* _handle;
     private bool _disposed;
 
+    // Managed functions registered on this connection, by name, freed once it is closed
+    private readonly Dictionary<string, GCHandle> _functions = new(StringComparer.OrdinalIgnoreCase);
+
+    // Virtual table modules registered on this connection, by name, freed along with the functions
+    private readonly Dictionary<string, GCHandle> _modules = new(StringComparer.OrdinalIgnoreCase);
+
+    // Statements and transactions not yet finalized. Their native side keeps the connection's
+    // registrations alive after it is closed, so the functions are only freed once they are gone.
+    private int _dependents;
+
+    // The managed busy handler and transaction hooks, kept and freed along with the functions
+    private GCHandle _busyHandler;
+    private GCHandle _commitHook;
+    private GCHandle _postCommitHook;
+    private GCHandle _rollbackHook;
+
     internal TursoConnection(void* handle)
     {
         _handle = handle;
@@ -34,10 +51,44 @@ public sealed unsafe class TursoConnection : IDisposable
             var result = TursoFFI.turso_connection_execute(_handle, sqlPtr, &rowsChanged);
             if (!result.success)
             {
-                var errorMessage = GetErrorMessage(result);
-                TursoFFI.turso_free_error_message(&result);
-                throw new TursoException($"Failed to execute SQL: {errorMessage}");
+                throw TursoException.FromResult("Failed to execute SQL", &result);
+            }
+        }
+    }
+
+    /// <summary>
+    /// Execute every statement in a script, in order, e.g. a migration shipped as a .sql file
+    /// </summary>
+    /// <param name="sql">The SQL statements to execute</param>
+    /// <param name="transactional">
+    /// Run the script in one transaction when the connection is in autocommit mode, so a failure
+    /// leaves the database untouched
+    /// </param>
+    /// <returns>The total number of rows changed by the script</returns>
+    /// <exception cref="TursoBatchException">A statement failed</exception>
+    public long ExecuteBatch(string sql, bool transactional = true)
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+        ArgumentException.ThrowIfNullOrEmpty(sql);
+
+        var sqlBytes = Encoding.UTF8.GetBytes(sql + '\0');
+        fixed (byte* sqlPtr = sqlBytes)
+        {
+            int failedStatementIndex = -1;
+            ulong rowsChanged = 0;
+            var result = TursoFFI.turso_connection_execute_batch(
+                _handle, sqlPtr, transactional, &failedStatementIndex, &rowsChanged);
+            if (!result.success)
+            {
+                var exception = TursoException.FromResult("Failed to execute batch", &result);
+                throw new TursoBatchException(
+                    exception.Message,
+                    exception.ErrorCode,
+                    exception.ExtendedErrorCode,
+                    failedStatementIndex,
+                    (long)rowsChanged);
             }
+            return (long)rowsChanged;
         }
     }
 
@@ -59,9 +110,7 @@ public sealed unsafe class TursoConnection : IDisposable
             var queryResult = TursoFFI.turso_connection_query_scalar_int(_handle, sqlPtr, &result);
             if (!queryResult.success)
             {
-                var errorMessage = GetErrorMessage(queryResult);
-                TursoFFI.turso_free_error_message(&queryResult);
-                throw new TursoException($"Failed to query scalar integer: {errorMessage}");
+                throw TursoException.FromResult("Failed to query scalar integer", &queryResult);
             }
         }
 
@@ -100,6 +149,12 @@ public sealed unsafe class TursoConnection : IDisposable
             var resultPtr = TursoFFI.turso_connection_query_scalar_string(_handle, sqlPtr);
             if (resultPtr == null)
             {
+                // Null is also returned for a NULL, non-text or missing value; only a
+                // recorded error means the query failed
+                if (TursoFFI.turso_last_error_code() != Turso.Native.TursoErrorCode.Ok)
+                {
+                    throw TursoException.FromLastError("Failed to query scalar string");
+                }
                 return null;
             }
 
@@ -131,9 +186,9 @@ public sealed unsafe class TursoConnection : IDisposable
             var statementHandle = TursoFFI.turso_connection_prepare(_handle, sqlPtr);
             if (statementHandle == null)
             {
-                throw new TursoException("Failed to prepare statement");
+                throw TursoException.FromLastError("Failed to prepare statement");
             }
-            return new TursoStatement(statementHandle);
+            return new TursoStatement(statementHandle, this);
         }
     }
 
@@ -149,22 +204,484 @@ public sealed unsafe class TursoConnection : IDisposable
     }
 
     /// <summary>
-    /// Begin a transaction with the specified behavior
+    /// Interrupt every statement running on this connection, including a result set that has
+    /// returned a row and not yet been read to the end. Its next step fails with
+    /// <see cref="TursoErrorCode.Interrupt"/>. Safe to call from another thread, e.g. from a
+    /// <see cref="System.Threading.CancellationToken"/> registration; does nothing when no
+    /// statement is running.
+    /// </summary>
+    public void Interrupt()
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+
+        var result = TursoFFI.turso_connection_interrupt(_handle);
+        if (!result.success)
+        {
+            throw TursoException.FromResult("Failed to interrupt connection", &result);
+        }
+    }
+
+    /// <summary>
+    /// Allow or forbid <see cref="LoadExtension"/> on this connection. Off by default, since a
+    /// loaded extension runs arbitrary native code. Extensions compiled into Turso are always
+    /// available and are not affected.
+    /// </summary>
+    /// <param name="enabled">Whether extensions may be loaded</param>
+    public void EnableExtensionLoading(bool enabled)
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+
+        var result = TursoFFI.turso_connection_enable_load_extension(_handle, enabled);
+        if (!result.success)
+        {
+            throw TursoException.FromResult("Failed to enable extension loading", &result);
+        }
+    }
+
+    /// <summary>
+    /// Load a Turso extension from a shared library. Requires <see cref="EnableExtensionLoading"/>.
+    /// </summary>
+    /// <param name="path">Path of the shared library</param>
+    /// <param name="entryPoint">The extension's entry point, or null for register_extension, the only one Turso calls</param>
+    public void LoadExtension(string path, string? entryPoint = null)
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+        ArgumentException.ThrowIfNullOrEmpty(path);
+
+        var pathBytes = Encoding.UTF8.GetBytes(path + '\0');
+        var entryPointBytes = entryPoint == null ? null : Encoding.UTF8.GetBytes(entryPoint + '\0');
+        fixed (byte* pathPtr = pathBytes)
+        fixed (byte* entryPointPtr = entryPointBytes)
+        {
+            var result = TursoFFI.turso_connection_load_extension(_handle, pathPtr, entryPointPtr);
+            if (!result.success)
+            {
+                throw TursoException.FromResult("Failed to load extension", &result);
+            }
+        }
+    }
+
+    /// <summary>
+    /// Register a scalar SQL function implemented in C#, or replace this connection's function
+    /// of the same name. Arguments arrive as long, double, string, byte[] or null; the result may
+    /// be any of those, an int, a float or a bool. An exception fails the statement with its message.
+    /// </summary>
+    /// <param name="name">The function name</param>
+    /// <param name="argumentCount">The number of arguments, or -1 for any number</param>
+    /// <param name="function">The function, called with the arguments of each invocation</param>
+    /// <param name="deterministic">Whether the function always returns the same result for the same arguments</param>
+    /// <remarks>
+    /// The function runs in the middle of a statement on this connection, so it must not use the
+    /// connection. At most 64 distinct function names can be registered in the process.
+    /// </remarks>
+    public void CreateScalarFunction(string name, int argumentCount, Func<object?[], object?> function, bool deterministic = false)
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+        ArgumentException.ThrowIfNullOrEmpty(name);
+        ArgumentNullException.ThrowIfNull(function);
+
+        var handle = GCHandle.Alloc(function);
+        var nameBytes = Encoding.UTF8.GetBytes(name + '\0');
+        fixed (byte* namePtr = nameBytes)
+        {
+            var result = TursoFFI.turso_connection_create_scalar_function(
+                _handle, namePtr, argumentCount, deterministic, &TursoFunctions.InvokeScalar, (void*)GCHandle.ToIntPtr(handle));
+            if (!result.success)
+            {
+                handle.Free();
+                throw TursoException.FromResult("Failed to create function", &result);
+            }
+        }
+        KeepFunction(name, handle);
+    }
+
+    /// <summary>
+    /// Register an aggregate SQL function implemented in C#, or replace this connection's function
+    /// of the same name. Each group starts from <paramref name="seed"/>, folds in its rows with
+    /// <paramref name="step"/> and reports <paramref name="resultSelector"/> of the final value.
+    /// Arguments and results use the same types as <see cref="CreateScalarFunction"/>.
+    /// </summary>
+    /// <param name="name">The function name</param>
+    /// <param name="argumentCount">The number of arguments, or -1 for any number</param>
+    /// <param name="seed">The accumulator of a group before its first row, shared by every group so it should be immutable</param>
+    /// <param name="step">Returns the accumulator after a row, given the one before it and the row's arguments</param>
+    /// <param name="resultSelector">Turns a group's final accumulator into the function result</param>
+    /// <remarks>
+    /// The callbacks run in the middle of a statement on this connection, so they must not use the
+    /// connection. Aggregates share the limit of 64 distinct function names with scalar functions.
+    /// <paramref name="resultSelector"/> also runs, with its result discarded, for a group whose step
+    /// threw and for a group left unfinished when its statement is reset or disposed early.
+    /// </remarks>
+    public void CreateAggregateFunction<TAccumulate>(
+        string name,
+        int argumentCount,
+        TAccumulate seed,
+        Func<TAccumulate, object?[], TAccumulate> step,
+        Func<TAccumulate, object?> resultSelector)
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+        ArgumentException.ThrowIfNullOrEmpty(name);
+        ArgumentNullException.ThrowIfNull(step);
+        ArgumentNullException.ThrowIfNull(resultSelector);
+
+        var handle = GCHandle.Alloc(new TursoAggregate<TAccumulate>(seed, step, resultSelector));
+        var nameBytes = Encoding.UTF8.GetBytes(name + '\0');
+        fixed (byte* namePtr = nameBytes)
+        {
+            var result = TursoFFI.turso_connection_create_aggregate_function(
+                _handle, namePtr, argumentCount,
+                &TursoFunctions.InvokeAggregateStep, &TursoFunctions.InvokeAggregateFinal, null, null,
+                (void*)GCHandle.ToIntPtr(handle));
+            if (!result.success)
+            {
+                handle.Free();
+                throw TursoException.FromResult("Failed to create aggregate function", &result);
+            }
+        }
+        KeepFunction(name, handle);
+    }
+
+    /// <summary>
+    /// Register a read-only virtual table module implemented in C#, or replace this connection's
+    /// module of the same name, so <c>CREATE VIRTUAL TABLE name USING module(arguments)</c> reads
+    /// its rows from <paramref name="module"/>
+    /// </summary>
+    /// <param name="name">The module name</param>
+    /// <param name="module">The module</param>
+    /// <remarks>
+    /// The module's callbacks run in the middle of a statement on this connection, so they must not
+    /// use the connection. At most 16 distinct module names can be registered in the process.
+    /// </remarks>
+    public void CreateModule(string name, TursoVirtualTableModule module)
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+        ArgumentException.ThrowIfNullOrEmpty(name);
+        ArgumentNullException.ThrowIfNull(module);
+
+        var handle = GCHandle.Alloc(module);
+        var callbacks = new TursoVTabModule
+        {
+            connect = &TursoVirtualTables.Connect,
+            best_index = &TursoVirtualTables.BestIndex,
+            disconnect = &TursoVirtualTables.Disconnect,
+            open = &TursoVirtualTables.Open,
+            close = &TursoVirtualTables.Close,
+            filter = &TursoVirtualTables.Filter,
+            next = &TursoVirtualTables.Next,
+            // A byte return stands in for the native bool, which UnmanagedCallersOnly can't return
+            eof = (delegate* unmanaged[Cdecl]<void*, bool>)(delegate* unmanaged[Cdecl]<void*, byte>)&TursoVirtualTables.Eof,
+            column = &TursoVirtualTables.Column,
+            rowid = &TursoVirtualTables.RowId
+        };
+        var nameBytes = Encoding.UTF8.GetBytes(name + '\0');
+        fixed (byte* namePtr = nameBytes)
+        {
+            var result = TursoFFI.turso_connection_create_module(_handle, namePtr, &callbacks, (void*)GCHandle.ToIntPtr(handle));
+            if (!result.success)
+            {
+                handle.Free();
+                throw TursoException.FromResult("Failed to create module", &result);
+            }
+        }
+        Keep(_modules, name, handle);
+    }
+
+    // Keeps a registered function alive, releasing the one it replaced
+    private void KeepFunction(string name, GCHandle handle) => Keep(_functions, name, handle);
+
+    private void Keep(Dictionary<string, GCHandle> registrations, string name, GCHandle handle)
+    {
+        lock (_functions)
+        {
+            if (registrations.Remove(name, out var replaced))
+            {
+                replaced.Free();
+            }
+            registrations[name] = handle;
+        }
+    }
+
+    // Called by a statement or transaction created on this connection
+    internal void AddDependent()
+    {
+        lock (_functions)
+        {
+            _dependents++;
+        }
+    }
+
+    // Called once a statement or transaction has been finalized, possibly on the finalizer thread
+    internal void RemoveDependent()
+    {
+        lock (_functions)
+        {
+            _dependents--;
+            if (_disposed && _dependents == 0)
+            {
+                FreeFunctions();
+            }
+        }
+    }
+
+    private void FreeFunctions()
+    {
+        foreach (var function in _functions.Values)
+        {
+            function.Free();
+        }
+        _functions.Clear();
+
+        foreach (var module in _modules.Values)
+        {
+            module.Free();
+        }
+        _modules.Clear();
+
+        foreach (var callback in new[] { _busyHandler, _commitHook, _postCommitHook, _rollbackHook })
+        {
+            if (callback.IsAllocated)
+            {
+                callback.Free();
+            }
+        }
+    }
+
+    /// <summary>
+    /// Set the behavior used when a transaction is begun without an explicit behavior,
+    /// including the implicit transaction of a transactional batch. Defaults to Deferred.
+    /// </summary>
+    /// <param name="behavior">The default transaction behavior</param>
+    public void SetDefaultTransactionBehavior(TursoTransactionBehavior behavior)
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+
+        var result = TursoFFI.turso_connection_set_default_transaction_behavior(_handle, (int)behavior);
+        if (!result.success)
+        {
+            throw TursoException.FromResult("Failed to set default transaction behavior", &result);
+        }
+    }
+
+    /// <summary>
+    /// Keep retrying a statement that finds the database locked by another connection for up
+    /// to <paramref name="timeout"/> before failing with <see cref="TursoErrorCode.Busy"/>.
+    /// <see cref="TimeSpan.Zero"/> fails straight away, which is the default.
+    /// </summary>
+    /// <param name="timeout">How long to keep retrying</param>
+    public void SetBusyTimeout(TimeSpan timeout)
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+        ArgumentOutOfRangeException.ThrowIfLessThan(timeout, TimeSpan.Zero);
+
+        var timeoutMs = (int)Math.Min(timeout.TotalMilliseconds, int.MaxValue);
+        var result = TursoFFI.turso_connection_set_busy_timeout(_handle, timeoutMs);
+        if (!result.success)
+        {
+            throw TursoException.FromResult("Failed to set busy timeout", &result);
+        }
+        KeepCallback(ref _busyHandler, default);
+    }
+
+    /// <summary>
+    /// Decide in C# whether to retry a statement that finds the database locked by another
+    /// connection, like sqlite3_busy_handler. The handler receives the number of times it has
+    /// already been called for the current step and returns true to retry straight away or
+    /// false to fail with <see cref="TursoErrorCode.Busy"/>; it may sleep before returning.
+    /// Replaces any busy timeout, and null removes the handler.
+    /// </summary>
+    /// <param name="handler">The busy handler, or null to fail straight away</param>
+    /// <remarks>
+    /// The handler runs in the middle of a statement on this connection, so it must not use the
+    /// connection. An exception thrown by the handler gives up like returning false.
+    /// </remarks>
+    public void SetBusyHandler(Func<int, bool>? handler)
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+
+        var handle = handler == null ? default : GCHandle.Alloc(handler);
+        var result = handler == null
+            ? TursoFFI.turso_connection_set_busy_handler(_handle, null, null)
+            : TursoFFI.turso_connection_set_busy_handler(_handle, &OnBusy, (void*)GCHandle.ToIntPtr(handle));
+        ThrowIfCallbackFailed(result, handle, "Failed to set busy handler");
+        KeepCallback(ref _busyHandler, handle);
+    }
+
+    // Keeps an installed callback alive, releasing the one it replaced
+    private void KeepCallback(ref GCHandle installed, GCHandle handle)
+    {
+        lock (_functions)
+        {
+            if (installed.IsAllocated)
+            {
+                installed.Free();
+            }
+            installed = handle;
+        }
+    }
+
+    [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+    private static int OnBusy(void* userData, int attempts)
+    {
+        try
+        {
+            var handler = (Func<int, bool>)GCHandle.FromIntPtr((IntPtr)userData).Target!;
+            return handler(attempts) ? 1 : 0;
+        }
+        catch
+        {
+            return 0;
+        }
+    }
+
+    /// <summary>
+    /// Copy the WAL into the database file. A checkpoint blocked by other connections is retried
+    /// according to the busy timeout and then fails with <see cref="TursoErrorCode.Busy"/>.
+    /// </summary>
+    /// <param name="mode">How far to go in waiting for other connections</param>
+    /// <returns>The number of frames in the WAL and how many of them were checkpointed</returns>
+    public TursoCheckpointResult Checkpoint(TursoCheckpointMode mode = TursoCheckpointMode.Passive)
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+
+        long walFrames = 0;
+        long checkpointedFrames = 0;
+        var result = TursoFFI.turso_connection_checkpoint(_handle, (int)mode, &walFrames, &checkpointedFrames);
+        if (!result.success)
+        {
+            throw TursoException.FromResult("Failed to checkpoint", &result);
+        }
+        return new TursoCheckpointResult(walFrames, checkpointedFrames);
+    }
+
+    /// <summary>
+    /// Number of frames in the WAL. A frame is a 24-byte header followed by one page, so it is
+    /// <c>PRAGMA page_size</c> + 24 bytes long.
+    /// </summary>
+    public long WalFrameCount
+    {
+        get
+        {
+            ObjectDisposedException.ThrowIf(_disposed, this);
+
+            ulong frameCount = 0;
+            var result = TursoFFI.turso_connection_wal_frame_count(_handle, &frameCount);
+            if (!result.success)
+            {
+                throw TursoException.FromResult("Failed to get WAL frame count", &result);
+            }
+            return (long)frameCount;
+        }
+    }
+
+    /// <summary>
+    /// Copy a WAL frame, e.g. to ship it to a replica that applies it with <see cref="InsertWalFrame"/>
     /// </summary>
-    /// <param name="behavior">The transaction behavior</param>
-    public void BeginTransaction(TursoTransactionBehavior behavior = TursoTransactionBehavior.Deferred)
+    /// <param name="frameNumber">The frame, from 1 to <see cref="WalFrameCount"/></param>
+    /// <param name="frame">Receives the frame; must be exactly one frame long</param>
+    public void GetWalFrame(long frameNumber, Span<byte> frame)
     {
         ObjectDisposedException.ThrowIf(_disposed, this);
+        ArgumentOutOfRangeException.ThrowIfLessThan(frameNumber, 1);
 
-        var result = TursoFFI.turso_connection_begin_transaction(_handle, (int)behavior);
+        fixed (byte* framePtr = frame)
+        {
+            var result = TursoFFI.turso_connection_wal_get_frame(_handle, (ulong)frameNumber, framePtr, (uint)frame.Length);
+            if (!result.success)
+            {
+                throw TursoException.FromResult("Failed to get WAL frame", &result);
+            }
+        }
+    }
+
+    /// <summary>
+    /// Start applying WAL frames copied from another database. Holds the write lock until
+    /// <see cref="EndWalInsert"/>.
+    /// </summary>
+    public void BeginWalInsert()
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+
+        var result = TursoFFI.turso_connection_wal_insert_begin(_handle);
         if (!result.success)
         {
-            var errorMessage = GetErrorMessage(result);
-            TursoFFI.turso_free_error_message(&result);
-            throw new TursoException($"Failed to begin transaction: {errorMessage}");
+            throw TursoException.FromResult("Failed to begin WAL insert", &result);
         }
     }
 
+    /// <summary>
+    /// Append a frame read with <see cref="GetWalFrame"/>. Frames must be applied in order,
+    /// between <see cref="BeginWalInsert"/> and <see cref="EndWalInsert"/>.
+    /// </summary>
+    /// <param name="frameNumber">The number the frame had in the source WAL</param>
+    /// <param name="frame">The frame</param>
+    public void InsertWalFrame(long frameNumber, ReadOnlySpan<byte> frame)
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+        ArgumentOutOfRangeException.ThrowIfLessThan(frameNumber, 1);
+
+        fixed (byte* framePtr = frame)
+        {
+            var result = TursoFFI.turso_connection_wal_insert_frame(_handle, (ulong)frameNumber, framePtr, (uint)frame.Length);
+            if (!result.success)
+            {
+                throw TursoException.FromResult("Failed to insert WAL frame", &result);
+            }
+        }
+    }
+
+    /// <summary>
+    /// Finish applying WAL frames and release the write lock taken by <see cref="BeginWalInsert"/>
+    /// </summary>
+    public void EndWalInsert()
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+
+        var result = TursoFFI.turso_connection_wal_insert_end(_handle);
+        if (!result.success)
+        {
+            throw TursoException.FromResult("Failed to end WAL insert", &result);
+        }
+    }
+
+    /// <summary>
+    /// Begin a transaction with the specified behavior. Transactions do not nest, so this
+    /// throws while a transaction is already open.
+    /// </summary>
+    /// <param name="behavior">The transaction behavior, or null for the connection default</param>
+    public void BeginTransaction(TursoTransactionBehavior? behavior = null)
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+
+        var result = TursoFFI.turso_connection_begin_transaction(_handle, behavior.HasValue ? (int)behavior.Value : -1);
+        if (!result.success)
+        {
+            throw TursoException.FromResult("Failed to begin transaction", &result);
+        }
+    }
+
+    /// <summary>
+    /// Begin a transaction owned by the returned object. Disposing it without committing or
+    /// rolling back applies <paramref name="dropBehavior"/>. Transactions do not nest, so this
+    /// throws while a transaction is already open.
+    /// </summary>
+    /// <param name="behavior">The transaction behavior, or null for the connection default</param>
+    /// <param name="dropBehavior">What to do with the transaction if it is disposed while still open</param>
+    /// <returns>The transaction</returns>
+    public TursoTransaction StartTransaction(
+        TursoTransactionBehavior? behavior = null,
+        TursoTransactionDropBehavior dropBehavior = TursoTransactionDropBehavior.Rollback)
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+
+        var transactionHandle = TursoFFI.turso_transaction_begin(
+            _handle, behavior.HasValue ? (int)behavior.Value : -1, (int)dropBehavior);
+        if (transactionHandle == null)
+        {
+            throw TursoException.FromLastError("Failed to begin transaction");
+        }
+        return new TursoTransaction(transactionHandle, this);
+    }
+
     /// <summary>
     /// Commit the current transaction
     /// </summary>
@@ -175,9 +692,7 @@ public sealed unsafe class TursoConnection : IDisposable
         var result = TursoFFI.turso_connection_commit_transaction(_handle);
         if (!result.success)
         {
-            var errorMessage = GetErrorMessage(result);
-            TursoFFI.turso_free_error_message(&result);
-            throw new TursoException($"Failed to commit transaction: {errorMessage}");
+            throw TursoException.FromResult("Failed to commit transaction", &result);
         }
     }
 
@@ -191,9 +706,110 @@ public sealed unsafe class TursoConnection : IDisposable
         var result = TursoFFI.turso_connection_rollback_transaction(_handle);
         if (!result.success)
         {
-            var errorMessage = GetErrorMessage(result);
-            TursoFFI.turso_free_error_message(&result);
-            throw new TursoException($"Failed to rollback transaction: {errorMessage}");
+            throw TursoException.FromResult("Failed to rollback transaction", &result);
+        }
+    }
+
+    /// <summary>
+    /// Ask <paramref name="hook"/> before a transaction on this connection commits, like
+    /// sqlite3_commit_hook. Returning false rolls the transaction back instead, and the commit
+    /// fails with <see cref="TursoErrorCode.ConstraintCommitHook"/>. The hook is asked once per
+    /// transaction, and a commit it approved can still fail, e.g. with
+    /// <see cref="TursoErrorCode.Busy"/>, so side effects belong in <see cref="SetPostCommitHook"/>.
+    /// Null removes the hook.
+    /// </summary>
+    /// <param name="hook">Returns true to let the transaction commit</param>
+    /// <remarks>
+    /// Transaction hooks run for transactions ended through this connection, for COMMIT and
+    /// ROLLBACK statements, and for writes made in autocommit mode, which get a transaction of their
+    /// own while any hook is installed. They run in the middle of that work, so they must not use
+    /// the connection. A hook that throws vetoes the commit.
+    /// </remarks>
+    public void SetCommitHook(Func<bool>? hook)
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+
+        var handle = hook == null ? default : GCHandle.Alloc(hook);
+        var result = hook == null
+            ? TursoFFI.turso_connection_set_commit_hook(_handle, null, null)
+            : TursoFFI.turso_connection_set_commit_hook(_handle, &OnCommit, (void*)GCHandle.ToIntPtr(handle));
+        ThrowIfCallbackFailed(result, handle, "Failed to set commit hook");
+        KeepCallback(ref _commitHook, handle);
+    }
+
+    /// <summary>
+    /// Call <paramref name="hook"/> after a transaction on this connection has committed. Unlike
+    /// <see cref="SetCommitHook"/> it only runs for commits that happened. Null removes the hook.
+    /// </summary>
+    /// <param name="hook">The hook</param>
+    /// <remarks>See <see cref="SetCommitHook"/> for when transaction hooks run. Exceptions thrown by the hook are ignored.</remarks>
+    public void SetPostCommitHook(Action? hook)
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+
+        var handle = hook == null ? default : GCHandle.Alloc(hook);
+        var result = hook == null
+            ? TursoFFI.turso_connection_set_post_commit_hook(_handle, null, null)
+            : TursoFFI.turso_connection_set_post_commit_hook(_handle, &OnTransactionEnded, (void*)GCHandle.ToIntPtr(handle));
+        ThrowIfCallbackFailed(result, handle, "Failed to set post-commit hook");
+        KeepCallback(ref _postCommitHook, handle);
+    }
+
+    /// <summary>
+    /// Call <paramref name="hook"/> after a transaction on this connection has rolled back, like
+    /// sqlite3_rollback_hook, including one rolled back by a commit hook. Null removes the hook.
+    /// </summary>
+    /// <param name="hook">The hook</param>
+    /// <remarks>See <see cref="SetCommitHook"/> for when transaction hooks run. Exceptions thrown by the hook are ignored.</remarks>
+    public void SetRollbackHook(Action? hook)
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+
+        var handle = hook == null ? default : GCHandle.Alloc(hook);
+        var result = hook == null
+            ? TursoFFI.turso_connection_set_rollback_hook(_handle, null, null)
+            : TursoFFI.turso_connection_set_rollback_hook(_handle, &OnTransactionEnded, (void*)GCHandle.ToIntPtr(handle));
+        ThrowIfCallbackFailed(result, handle, "Failed to set rollback hook");
+        KeepCallback(ref _rollbackHook, handle);
+    }
+
+    // Releases the handle of a callback the native side didn't take
+    private static void ThrowIfCallbackFailed(TursoFFIResult result, GCHandle handle, string message)
+    {
+        if (!result.success)
+        {
+            if (handle.IsAllocated)
+            {
+                handle.Free();
+            }
+            throw TursoException.FromResult(message, &result);
+        }
+    }
+
+    [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+    private static int OnCommit(void* userData)
+    {
+        try
+        {
+            var hook = (Func<bool>)GCHandle.FromIntPtr((IntPtr)userData).Target!;
+            return hook() ? 0 : 1;
+        }
+        catch
+        {
+            return 1;
+        }
+    }
+
+    [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+    private static void OnTransactionEnded(void* userData)
+    {
+        try
+        {
+            ((Action)GCHandle.FromIntPtr((IntPtr)userData).Target!)();
+        }
+        catch
+        {
+            // The transaction has already ended, so there is nothing to fail
         }
     }
 
@@ -211,14 +827,69 @@ public sealed unsafe class TursoConnection : IDisposable
             var result = TursoFFI.turso_connection_is_autocommit(_handle, &isAutoCommit);
             if (!result.success)
             {
-                var errorMessage = GetErrorMessage(result);
-                TursoFFI.turso_free_error_message(&result);
-                throw new TursoException($"Failed to check autocommit status: {errorMessage}");
+                throw TursoException.FromResult("Failed to check autocommit status", &result);
             }
             return isAutoCommit;
         }
     }
 
+    /// <summary>
+    /// Rowid of the most recent successful INSERT on this connection
+    /// </summary>
+    public long LastInsertRowId
+    {
+        get
+        {
+            ObjectDisposedException.ThrowIf(_disposed, this);
+
+            long rowId = 0;
+            var result = TursoFFI.turso_connection_last_insert_rowid(_handle, &rowId);
+            if (!result.success)
+            {
+                throw TursoException.FromResult("Failed to get last insert rowid", &result);
+            }
+            return rowId;
+        }
+    }
+
+    /// <summary>
+    /// Number of rows changed by the most recently completed statement
+    /// </summary>
+    public long Changes
+    {
+        get
+        {
+            ObjectDisposedException.ThrowIf(_disposed, this);
+
+            long changes = 0;
+            var result = TursoFFI.turso_connection_changes(_handle, &changes);
+            if (!result.success)
+            {
+                throw TursoException.FromResult("Failed to get changes", &result);
+            }
+            return changes;
+        }
+    }
+
+    /// <summary>
+    /// Number of rows changed since the connection was opened
+    /// </summary>
+    public long TotalChanges
+    {
+        get
+        {
+            ObjectDisposedException.ThrowIf(_disposed, this);
+
+            long totalChanges = 0;
+            var result = TursoFFI.turso_connection_total_changes(_handle, &totalChanges);
+            if (!result.success)
+            {
+                throw TursoException.FromResult("Failed to get total changes", &result);
+            }
+            return totalChanges;
+        }
+    }
+
     /// <summary>
     /// Dispose the connection and free associated resources
     /// </summary>
@@ -229,12 +900,18 @@ public sealed unsafe class TursoConnection : IDisposable
             var result = TursoFFI.turso_connection_close(_handle);
             if (!result.success)
             {
-                var errorMessage = GetErrorMessage(result);
-                TursoFFI.turso_free_error_message(&result);
-                throw new TursoException($"Failed to close connection: {errorMessage}");
+                throw TursoException.FromResult("Failed to close connection", &result);
             }
             _handle = null;
-            _disposed = true;
+
+            lock (_functions)
+            {
+                _disposed = true;
+                if (_dependents == 0)
+                {
+                    FreeFunctions();
+                }
+            }
         }
         GC.SuppressFinalize(this);
     }
@@ -243,19 +920,4 @@ public sealed unsafe class TursoConnection : IDisposable
     {
         Dispose();
     }
-
-    private static string GetErrorMessage(TursoFFIResult result)
-    {
-        if (result.error_message == null)
-            return "Unknown error";
-
-        try
-        {
-            return Marshal.PtrToStringUTF8((IntPtr)result.error_message) ?? "Unknown error";
-        }
-        catch
-        {
-            return "Unknown error";
-        }
-    }
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 using System;
+using System.Runtime.CompilerServices;
 using System.Runtime.InteropServices;
 using System.Text;
 using Turso.Native;
@@ -13,6 +14,9 @@ public sealed unsafe class TursoDatabase : IDisposable
     private void* _handle;
     private bool _disposed;
 
+    // Error handler of the running auto-checkpoint task, freed once the task has stopped
+    private GCHandle _autoCheckpointErrorHandler;
+
     private TursoDatabase(void* handle)
     {
         _handle = handle;
@@ -27,7 +31,7 @@ public sealed unsafe class TursoDatabase : IDisposable
         var handle = TursoFFI.turso_database_open_memory();
         if (handle == null)
         {
-            throw new TursoException("Failed to open in-memory database");
+            throw TursoException.FromLastError("Failed to open in-memory database");
         }
         return new TursoDatabase(handle);
     }
@@ -47,12 +51,63 @@ public sealed unsafe class TursoDatabase : IDisposable
             var handle = TursoFFI.turso_database_open_file(pathPtr);
             if (handle == null)
             {
-                throw new TursoException($"Failed to open database file: {path}");
+                throw TursoException.FromLastError($"Failed to open database file {path}");
             }
             return new TursoDatabase(handle);
         }
     }
 
+    /// <summary>
+    /// Open a database with explicit options
+    /// </summary>
+    /// <param name="path">Path to the database file, or <c>:memory:</c></param>
+    /// <param name="options">Options to open the database with, or null for the defaults</param>
+    /// <returns>A new TursoDatabase instance</returns>
+    public static TursoDatabase Open(string path, TursoDatabaseOptions? options = null)
+    {
+        ArgumentException.ThrowIfNullOrEmpty(path);
+        options ??= new TursoDatabaseOptions();
+
+        var nativeOptions = new Turso.Native.TursoOpenOptions
+        {
+            read_only = options.ReadOnly,
+            create_if_missing = options.CreateIfMissing,
+            enable_mvcc = options.EnableMvcc,
+            enable_indexes = options.EnableIndexes,
+            io_backend = (int)options.IoBackend
+        };
+
+        var pathBytes = Encoding.UTF8.GetBytes(path + '\0');
+        fixed (byte* pathPtr = pathBytes)
+        {
+            var handle = TursoFFI.turso_database_open_with_options(pathPtr, &nativeOptions);
+            if (handle == null)
+            {
+                throw TursoException.FromLastError($"Failed to open database file {path}");
+            }
+            return new TursoDatabase(handle);
+        }
+    }
+
+    /// <summary>
+    /// The IO backend the database is using, after any fallback from io_uring
+    /// </summary>
+    public TursoIoBackend IoBackend
+    {
+        get
+        {
+            ObjectDisposedException.ThrowIf(_disposed, this);
+
+            Turso.Native.TursoIoBackend backend;
+            var result = TursoFFI.turso_database_get_io_backend(_handle, &backend);
+            if (!result.success)
+            {
+                throw TursoException.FromResult("Failed to get IO backend", &result);
+            }
+            return (TursoIoBackend)(int)backend;
+        }
+    }
+
     /// <summary>
     /// Create a new connection to this database
     /// </summary>
@@ -64,48 +119,122 @@ public sealed unsafe class TursoDatabase : IDisposable
         var connectionHandle = TursoFFI.turso_connection_open(_handle);
         if (connectionHandle == null)
         {
-            throw new TursoException("Failed to create database connection");
+            throw TursoException.FromLastError("Failed to create database connection");
         }
         return new TursoConnection(connectionHandle);
     }
 
     /// <summary>
-    /// Dispose the database and free associated resources
+    /// Checkpoint the database in the background whenever the WAL passes a limit in
+    /// <paramref name="options"/>, replacing any auto-checkpoint already running. Busy
+    /// checkpoints are retried on the next poll.
     /// </summary>
-    public void Dispose()
+    /// <param name="options">When and how to checkpoint</param>
+    /// <param name="onError">
+    /// Called on a background thread with any other checkpoint failure. It may stop or restart the
+    /// auto-checkpoint, which then returns without waiting for the handler to finish.
+    /// </param>
+    public void StartAutoCheckpoint(TursoAutoCheckpointOptions options, Action<TursoException>? onError = null)
     {
-        if (!_disposed && _handle != null)
+        ObjectDisposedException.ThrowIf(_disposed, this);
+        ArgumentNullException.ThrowIfNull(options);
+        ArgumentOutOfRangeException.ThrowIfNegative(options.WalFrameThreshold);
+
+        var nativeOptions = new Turso.Native.TursoAutoCheckpointOptions
         {
-            var result = TursoFFI.turso_database_close(_handle);
-            if (!result.success)
+            wal_frame_threshold = (ulong)options.WalFrameThreshold,
+            max_wal_age_ms = ToMilliseconds(options.MaxWalAge ?? TimeSpan.Zero),
+            poll_interval_ms = ToMilliseconds(options.PollInterval),
+            mode = (int)options.Mode
+        };
+
+        var handler = onError == null ? default : GCHandle.Alloc(onError);
+        var result = onError == null
+            ? TursoFFI.turso_database_start_auto_checkpoint(_handle, &nativeOptions, null, null)
+            : TursoFFI.turso_database_start_auto_checkpoint(
+                _handle, &nativeOptions, &OnAutoCheckpointError, (void*)GCHandle.ToIntPtr(handler));
+        if (!result.success)
+        {
+            if (handler.IsAllocated)
             {
-                var errorMessage = GetErrorMessage(result);
-                TursoFFI.turso_free_error_message(&result);
-                throw new TursoException($"Failed to close database: {errorMessage}");
+                handler.Free();
             }
-            _handle = null;
-            _disposed = true;
+            throw TursoException.FromResult("Failed to start auto-checkpoint", &result);
         }
-        GC.SuppressFinalize(this);
+
+        // The previous task, if any, was stopped before the new one started
+        ReleaseAutoCheckpointErrorHandler();
+        _autoCheckpointErrorHandler = handler;
     }
 
-    ~TursoDatabase()
+    /// <summary>
+    /// Stop the auto-checkpoint started by <see cref="StartAutoCheckpoint"/>, waiting for a
+    /// checkpoint in progress. Does nothing if none is running.
+    /// </summary>
+    public void StopAutoCheckpoint()
     {
-        Dispose();
+        ObjectDisposedException.ThrowIf(_disposed, this);
+
+        var result = TursoFFI.turso_database_stop_auto_checkpoint(_handle);
+        if (!result.success)
+        {
+            throw TursoException.FromResult("Failed to stop auto-checkpoint", &result);
+        }
+        ReleaseAutoCheckpointErrorHandler();
     }
 
-    private static string GetErrorMessage(TursoFFIResult result)
+    private static ulong ToMilliseconds(TimeSpan value)
     {
-        if (result.error_message == null)
-            return "Unknown error";
+        ArgumentOutOfRangeException.ThrowIfLessThan(value, TimeSpan.Zero);
+        return (ulong)value.TotalMilliseconds;
+    }
+
+    private void ReleaseAutoCheckpointErrorHandler()
+    {
+        if (_autoCheckpointErrorHandler.IsAllocated)
+        {
+            _autoCheckpointErrorHandler.Free();
+        }
+    }
 
+    [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+    private static void OnAutoCheckpointError(void* userData, Turso.Native.TursoErrorCode code, byte* message)
+    {
         try
         {
-            return Marshal.PtrToStringUTF8((IntPtr)result.error_message) ?? "Unknown error";
+            var onError = (Action<TursoException>)GCHandle.FromIntPtr((IntPtr)userData).Target!;
+            var text = Marshal.PtrToStringUTF8((IntPtr)message) ?? "Unknown error";
+            onError(new TursoException($"Auto-checkpoint failed: {text}", (TursoErrorCode)(int)code, (TursoErrorCode)(int)code));
         }
         catch
         {
-            return "Unknown error";
+            // Nothing can be done with a failing handler on the maintenance thread
+        }
+    }
+
+    /// <summary>
+    /// Dispose the database and free associated resources
+    /// </summary>
+    public void Dispose()
+    {
+        if (!_disposed && _handle != null)
+        {
+            var result = TursoFFI.turso_database_close(_handle);
+            if (!result.success)
+            {
+                throw TursoException.FromResult("Failed to close database", &result);
+            }
+            _handle = null;
+            _disposed = true;
+
+            // Closing stopped the auto-checkpoint task
+            ReleaseAutoCheckpointErrorHandler();
         }
+        GC.SuppressFinalize(this);
+    }
+
+    ~TursoDatabase()
+    {
+        Dispose();
     }
 }
\ No newline at end of file
@@ -0,0 +1,32 @@
+namespace TursoSharp;
+
+/// <summary>
+/// Options for <see cref="TursoDatabase.Open"/>
+/// </summary>
+public sealed class TursoDatabaseOptions
+{
+    /// <summary>
+    /// Open the database for reading only; writes fail. Defaults to false.
+    /// </summary>
+    public bool ReadOnly { get; init; }
+
+    /// <summary>
+    /// Create the database file if it does not exist. Defaults to true.
+    /// </summary>
+    public bool CreateIfMissing { get; init; } = true;
+
+    /// <summary>
+    /// Enable multi-version concurrency control. Defaults to false.
+    /// </summary>
+    public bool EnableMvcc { get; init; }
+
+    /// <summary>
+    /// Enable the engine's experimental index support. Defaults to false.
+    /// </summary>
+    public bool EnableIndexes { get; init; }
+
+    /// <summary>
+    /// IO backend to use. Defaults to <see cref="TursoIoBackend.Default"/>.
+    /// </summary>
+    public TursoIoBackend IoBackend { get; init; } = TursoIoBackend.Default;
+}
@@ -0,0 +1,44 @@
+namespace TursoSharp;
+
+/// <summary>
+/// Result codes reported by Turso, compatible with SQLite's primary and extended result codes.
+/// Extended codes carry their primary code in the low byte.
+/// </summary>
+public enum TursoErrorCode
+{
+    Ok = 0,
+    Error = 1,
+    Internal = 2,
+    Perm = 3,
+    Abort = 4,
+    Busy = 5,
+    Locked = 6,
+    NoMem = 7,
+    ReadOnly = 8,
+    Interrupt = 9,
+    IoErr = 10,
+    Corrupt = 11,
+    NotFound = 12,
+    Full = 13,
+    CantOpen = 14,
+    Schema = 17,
+    TooBig = 18,
+    Constraint = 19,
+    Mismatch = 20,
+    Misuse = 21,
+    Range = 25,
+    NotADb = 26,
+    IoErrRead = 266,
+    IoErrWrite = 778,
+    IoErrLock = 3850,
+    ConstraintCheck = 275,
+    ConstraintForeignKey = 787,
+    ConstraintNotNull = 1299,
+    ConstraintPrimaryKey = 1555,
+    ConstraintUnique = 2067,
+
+    /// <summary>
+    /// A commit hook turned the commit into a rollback
+    /// </summary>
+    ConstraintCommitHook = 531
+}
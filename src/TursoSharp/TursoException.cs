@@ -1,4 +1,6 @@
 using System;
+using System.Runtime.InteropServices;
+using Turso.Native;
 
 namespace TursoSharp;
 
@@ -14,4 +16,57 @@ public class TursoException : Exception
     public TursoException(string message, Exception innerException) : base(message, innerException)
     {
     }
-}
\ No newline at end of file
+
+    public TursoException(string message, TursoErrorCode errorCode, TursoErrorCode extendedErrorCode) : base(message)
+    {
+        ErrorCode = errorCode;
+        ExtendedErrorCode = extendedErrorCode;
+    }
+
+    /// <summary>
+    /// Primary result code, e.g. <see cref="TursoErrorCode.Constraint"/> or <see cref="TursoErrorCode.Busy"/>
+    /// </summary>
+    public TursoErrorCode ErrorCode { get; } = TursoErrorCode.Error;
+
+    /// <summary>
+    /// Extended result code, e.g. <see cref="TursoErrorCode.ConstraintUnique"/>. Equal to
+    /// <see cref="ErrorCode"/> when there is no more specific code.
+    /// </summary>
+    public TursoErrorCode ExtendedErrorCode { get; } = TursoErrorCode.Error;
+
+    // Builds the exception for a failed call and frees the native error message
+    internal static unsafe TursoException FromResult(string context, TursoFFIResult* result)
+    {
+        var message = result->error_message == null
+            ? "Unknown error"
+            : Marshal.PtrToStringUTF8((IntPtr)result->error_message) ?? "Unknown error";
+        var errorCode = (TursoErrorCode)(int)result->error_code;
+        var extendedErrorCode = (TursoErrorCode)(int)result->extended_error_code;
+        TursoFFI.turso_free_error_message(result);
+        return new TursoException($"{context}: {message}", errorCode, extendedErrorCode);
+    }
+
+    // Builds the exception for a failed call that reports through the thread's last error,
+    // i.e. one returning a null handle or a failure status
+    internal static unsafe TursoException FromLastError(string context)
+    {
+        var errorCode = (TursoErrorCode)(int)TursoFFI.turso_last_error_code();
+        var extendedErrorCode = (TursoErrorCode)(int)TursoFFI.turso_last_error_extended_code();
+
+        var message = "Unknown error";
+        var messagePtr = TursoFFI.turso_last_error_message();
+        if (messagePtr != null)
+        {
+            try
+            {
+                message = Marshal.PtrToStringUTF8((IntPtr)messagePtr) ?? message;
+            }
+            finally
+            {
+                TursoFFI.turso_free_string(messagePtr);
+            }
+        }
+
+        return new TursoException($"{context}: {message}", errorCode, extendedErrorCode);
+    }
+}
@@ -0,0 +1,224 @@
+using System;
+using System.Runtime.CompilerServices;
+using System.Runtime.InteropServices;
+using System.Text;
+using Turso.Native;
+
+namespace TursoSharp;
+
+/// <summary>
+/// Native callbacks for SQL functions implemented in C#. The user data of every registration
+/// is a GCHandle to the managed function or TursoAggregate, kept alive by the connection that registered it.
+/// </summary>
+internal static unsafe class TursoFunctions
+{
+    [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+    internal static void InvokeScalar(void* userData, void* context, int argc, void** argv)
+    {
+        try
+        {
+            var function = (Func<object?[], object?>)GCHandle.FromIntPtr((IntPtr)userData).Target!;
+            SetResult(context, function(ReadArguments(argc, argv)));
+        }
+        catch (Exception ex)
+        {
+            SetError(context, ex.Message);
+        }
+    }
+
+    [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+    internal static void InvokeAggregateStep(void* userData, void* context, int argc, void** argv)
+    {
+        try
+        {
+            var aggregate = (TursoAggregate)GCHandle.FromIntPtr((IntPtr)userData).Target!;
+            var slot = (IntPtr*)TursoFFI.turso_aggregate_context(context, sizeof(IntPtr));
+            if (slot == null)
+            {
+                SetError(context, "Aggregate state is not available");
+                return;
+            }
+
+            // The first row of a group allocates its accumulator, released by InvokeAggregateFinal
+            if (*slot == IntPtr.Zero)
+            {
+                *slot = GCHandle.ToIntPtr(GCHandle.Alloc(aggregate.NewGroup()));
+            }
+            aggregate.Step(GCHandle.FromIntPtr(*slot).Target!, ReadArguments(argc, argv));
+        }
+        catch (Exception ex)
+        {
+            SetError(context, ex.Message);
+        }
+    }
+
+    [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+    internal static void InvokeAggregateFinal(void* userData, void* context)
+    {
+        try
+        {
+            var aggregate = (TursoAggregate)GCHandle.FromIntPtr((IntPtr)userData).Target!;
+
+            // No storage means the group had no rows, which aggregates the seed alone
+            var slot = (IntPtr*)TursoFFI.turso_aggregate_context(context, 0);
+            object group;
+            if (slot != null && *slot != IntPtr.Zero)
+            {
+                var handle = GCHandle.FromIntPtr(*slot);
+                group = handle.Target!;
+                handle.Free();
+                *slot = IntPtr.Zero;
+            }
+            else
+            {
+                group = aggregate.NewGroup();
+            }
+            SetResult(context, aggregate.Final(group));
+        }
+        catch (Exception ex)
+        {
+            SetError(context, ex.Message);
+        }
+    }
+
+    // Arguments arrive as long, double, string, byte[] or null
+    internal static object?[] ReadArguments(int argc, void** argv)
+    {
+        var arguments = new object?[argc];
+        for (var i = 0; i < argc; i++)
+        {
+            arguments[i] = ReadValue(argv[i]);
+        }
+        return arguments;
+    }
+
+    private static object? ReadValue(void* value)
+    {
+        switch ((TursoColumnType)TursoFFI.turso_value_type(value))
+        {
+            case TursoColumnType.Integer:
+                return TursoFFI.turso_value_int64(value);
+            case TursoColumnType.Real:
+                return TursoFFI.turso_value_double(value);
+            case TursoColumnType.Text:
+                var text = TursoFFI.turso_value_text(value);
+                try
+                {
+                    return Marshal.PtrToStringUTF8((IntPtr)text) ?? string.Empty;
+                }
+                finally
+                {
+                    TursoFFI.turso_free_string(text);
+                }
+            case TursoColumnType.Blob:
+                int length = 0;
+                var data = TursoFFI.turso_value_blob(value, &length);
+                if (data == null)
+                {
+                    return Array.Empty<byte>();
+                }
+                try
+                {
+                    var bytes = new byte[length];
+                    Marshal.Copy((IntPtr)data, bytes, 0, length);
+                    return bytes;
+                }
+                finally
+                {
+                    TursoFFI.turso_free_blob(data, length);
+                }
+            default:
+                return null;
+        }
+    }
+
+    internal static void SetResult(void* context, object? value)
+    {
+        TursoFFIResult result;
+        switch (value)
+        {
+            case null:
+                result = TursoFFI.turso_result_null(context);
+                break;
+            case long l:
+                result = TursoFFI.turso_result_int64(context, l);
+                break;
+            case int i:
+                result = TursoFFI.turso_result_int64(context, i);
+                break;
+            case bool b:
+                result = TursoFFI.turso_result_int64(context, b ? 1 : 0);
+                break;
+            case double d:
+                result = TursoFFI.turso_result_double(context, d);
+                break;
+            case float f:
+                result = TursoFFI.turso_result_double(context, f);
+                break;
+            case string s:
+                var textBytes = Encoding.UTF8.GetBytes(s + '\0');
+                fixed (byte* textPtr = textBytes)
+                {
+                    result = TursoFFI.turso_result_text(context, textPtr);
+                }
+                break;
+            case byte[] bytes:
+                fixed (byte* dataPtr = bytes)
+                {
+                    result = TursoFFI.turso_result_blob(context, dataPtr, bytes.Length);
+                }
+                break;
+            default:
+                SetError(context, $"Unsupported result type {value.GetType()}");
+                return;
+        }
+
+        if (!result.success)
+        {
+            // Reported to the statement through the context, which fails it with this message
+            TursoFFI.turso_free_error_message(&result);
+            SetError(context, "Failed to set function result");
+        }
+    }
+
+    internal static void SetError(void* context, string message)
+    {
+        var messageBytes = Encoding.UTF8.GetBytes(message.Replace('\0', ' ') + '\0');
+        fixed (byte* messagePtr = messageBytes)
+        {
+            var result = TursoFFI.turso_result_error(context, messagePtr);
+            if (!result.success)
+            {
+                TursoFFI.turso_free_error_message(&result);
+            }
+        }
+    }
+}
+
+/// <summary>
+/// A registered aggregate function, with its per-group accumulator kept as an object
+/// </summary>
+internal abstract class TursoAggregate
+{
+    internal abstract object NewGroup();
+
+    internal abstract void Step(object group, object?[] arguments);
+
+    internal abstract object? Final(object group);
+}
+
+internal sealed class TursoAggregate<TAccumulate>(
+    TAccumulate seed,
+    Func<TAccumulate, object?[], TAccumulate> step,
+    Func<TAccumulate, object?> resultSelector) : TursoAggregate
+{
+    internal override object NewGroup() => new StrongBox<TAccumulate>(seed);
+
+    internal override void Step(object group, object?[] arguments)
+    {
+        var accumulator = (StrongBox<TAccumulate>)group;
+        accumulator.Value = step(accumulator.Value!, arguments);
+    }
+
+    internal override object? Final(object group) => resultSelector(((StrongBox<TAccumulate>)group).Value!);
+}
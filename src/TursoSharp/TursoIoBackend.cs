@@ -0,0 +1,27 @@
+namespace TursoSharp;
+
+/// <summary>
+/// IO backend used to access the database file
+/// </summary>
+public enum TursoIoBackend
+{
+    /// <summary>
+    /// The platform default; <c>:memory:</c> paths use <see cref="Memory"/>
+    /// </summary>
+    Default = 0,
+
+    /// <summary>
+    /// Keep the database in memory
+    /// </summary>
+    Memory = 1,
+
+    /// <summary>
+    /// Blocking file IO through system calls
+    /// </summary>
+    Syscall = 2,
+
+    /// <summary>
+    /// io_uring on Linux. Falls back to <see cref="Syscall"/> when it is unavailable.
+    /// </summary>
+    IoUring = 3
+}
@@ -37,6 +37,11 @@ public sealed class TursoResultSet : IEnumerable<TursoRow>, IDisposable
     /// Move to the next row in the result set
     /// </summary>
     /// <returns>True if a row is available, false if no more rows</returns>
+    /// <exception cref="TursoException">
+    /// Stepping failed. <see cref="TursoException.ErrorCode"/> is <see cref="TursoErrorCode.Busy"/> when the
+    /// database is locked, in which case Read can be called again to retry, or
+    /// <see cref="TursoErrorCode.Interrupt"/> when the connection was interrupted.
+    /// </exception>
     public bool Read()
     {
         ObjectDisposedException.ThrowIf(_disposed, this);
@@ -48,7 +53,7 @@ public sealed class TursoResultSet : IEnumerable<TursoRow>, IDisposable
         {
             1 => true,   // Row available
             0 => false,  // Done
-            _ => throw new TursoException("Error stepping through result set")
+            _ => throw TursoException.FromLastError("Error stepping through result set")
         };
     }
 
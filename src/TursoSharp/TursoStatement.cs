@@ -12,22 +12,26 @@ public sealed unsafe class TursoStatement : IDisposable
 {
     private void* _handle;
     private bool _disposed;
+    private readonly TursoConnection _connection;
 
-    internal TursoStatement(void* handle)
+    internal TursoStatement(void* handle, TursoConnection connection)
     {
         _handle = handle;
+        _connection = connection;
+        _connection.AddDependent();
     }
 
     /// <summary>
     /// Execute a step of the statement and return the result
     /// </summary>
     /// <returns>
-    /// 1 if a row is available, 0 if done, -1 if error
+    /// 1 if a row is available, 0 if done, 2 if the database is busy, 3 if interrupted,
+    /// 4 on misuse, -1 if error
     /// </returns>
     public int Step()
     {
         ObjectDisposedException.ThrowIf(_disposed, this);
-        return TursoFFI.turso_statement_step(_handle);
+        return (int)TursoFFI.turso_statement_step(_handle);
     }
 
     /// <summary>
@@ -42,6 +46,44 @@ public sealed unsafe class TursoStatement : IDisposable
         }
     }
 
+    /// <summary>
+    /// Number of rows changed by the statement, once <see cref="Step"/> has returned 0
+    /// </summary>
+    public long Changes
+    {
+        get
+        {
+            ObjectDisposedException.ThrowIf(_disposed, this);
+
+            long changes = 0;
+            var result = TursoFFI.turso_statement_changes(_handle, &changes);
+            if (!result.success)
+            {
+                throw TursoException.FromResult("Failed to get changes", &result);
+            }
+            return changes;
+        }
+    }
+
+    /// <summary>
+    /// Rowid of the most recent successful INSERT on the statement's connection
+    /// </summary>
+    public long LastInsertRowId
+    {
+        get
+        {
+            ObjectDisposedException.ThrowIf(_disposed, this);
+
+            long rowId = 0;
+            var result = TursoFFI.turso_statement_last_insert_rowid(_handle, &rowId);
+            if (!result.success)
+            {
+                throw TursoException.FromResult("Failed to get last insert rowid", &result);
+            }
+            return rowId;
+        }
+    }
+
     /// <summary>
     /// Get the name of a column by index
     /// </summary>
@@ -196,9 +238,7 @@ public sealed unsafe class TursoStatement : IDisposable
         var result = TursoFFI.turso_statement_bind_int64(_handle, parameterIndex, value);
         if (!result.success)
         {
-            var errorMessage = GetErrorMessage(result);
-            TursoFFI.turso_free_error_message(&result);
-            throw new TursoException($"Failed to bind parameter: {errorMessage}");
+            throw TursoException.FromResult("Failed to bind parameter", &result);
         }
     }
 
@@ -212,9 +252,7 @@ public sealed unsafe class TursoStatement : IDisposable
         var result = TursoFFI.turso_statement_bind_double(_handle, parameterIndex, value);
         if (!result.success)
         {
-            var errorMessage = GetErrorMessage(result);
-            TursoFFI.turso_free_error_message(&result);
-            throw new TursoException($"Failed to bind parameter: {errorMessage}");
+            throw TursoException.FromResult("Failed to bind parameter", &result);
         }
     }
 
@@ -234,9 +272,7 @@ public sealed unsafe class TursoStatement : IDisposable
             var result = TursoFFI.turso_statement_bind_text(_handle, parameterIndex, valuePtr);
             if (!result.success)
             {
-                var errorMessage = GetErrorMessage(result);
-                TursoFFI.turso_free_error_message(&result);
-                throw new TursoException($"Failed to bind parameter: {errorMessage}");
+                throw TursoException.FromResult("Failed to bind parameter", &result);
             }
         }
     }
@@ -259,9 +295,7 @@ public sealed unsafe class TursoStatement : IDisposable
             var result = TursoFFI.turso_statement_bind_blob(_handle, parameterIndex, dataPtr, value.Length);
             if (!result.success)
             {
-                var errorMessage = GetErrorMessage(result);
-                TursoFFI.turso_free_error_message(&result);
-                throw new TursoException($"Failed to bind parameter: {errorMessage}");
+                throw TursoException.FromResult("Failed to bind parameter", &result);
             }
         }
     }
@@ -316,65 +350,127 @@ public sealed unsafe class TursoStatement : IDisposable
         var result = TursoFFI.turso_statement_bind_null(_handle, parameterIndex);
         if (!result.success)
         {
-            var errorMessage = GetErrorMessage(result);
-            TursoFFI.turso_free_error_message(&result);
-            throw new TursoException($"Failed to bind parameter: {errorMessage}");
+            throw TursoException.FromResult("Failed to bind parameter", &result);
         }
     }
 
     /// <summary>
-    /// Reset the statement to be executed again
+    /// Bind a list of integers as a JSON array, to be expanded with json_each, e.g.
+    /// <c>SELECT * FROM users WHERE id IN (SELECT value FROM json_each(?))</c>. The parameter
+    /// is JSON text, not a carray pointer, so <c>carray(?)</c> does not accept it.
     /// </summary>
-    public void Reset()
+    public void BindInt64Array(int parameterIndex, ReadOnlySpan<long> values)
     {
         ObjectDisposedException.ThrowIf(_disposed, this);
 
-        var result = TursoFFI.turso_statement_reset(_handle);
-        if (!result.success)
+        fixed (long* valuesPtr = values)
         {
-            var errorMessage = GetErrorMessage(result);
-            TursoFFI.turso_free_error_message(&result);
-            throw new TursoException($"Failed to reset statement: {errorMessage}");
+            var result = TursoFFI.turso_statement_bind_int64_array(_handle, parameterIndex, valuesPtr, values.Length);
+            if (!result.success)
+            {
+                throw TursoException.FromResult("Failed to bind parameter", &result);
+            }
         }
     }
 
     /// <summary>
-    /// Dispose the statement and free associated resources
+    /// Bind a list of doubles as a JSON array, to be expanded with json_each rather than
+    /// carray. NaN and infinities become NULL elements.
     /// </summary>
-    public void Dispose()
+    public void BindDoubleArray(int parameterIndex, ReadOnlySpan<double> values)
     {
-        if (!_disposed && _handle != null)
+        ObjectDisposedException.ThrowIf(_disposed, this);
+
+        fixed (double* valuesPtr = values)
         {
-            var result = TursoFFI.turso_statement_finalize(_handle);
+            var result = TursoFFI.turso_statement_bind_double_array(_handle, parameterIndex, valuesPtr, values.Length);
             if (!result.success)
             {
-                var errorMessage = GetErrorMessage(result);
-                TursoFFI.turso_free_error_message(&result);
-                throw new TursoException($"Failed to finalize statement: {errorMessage}");
+                throw TursoException.FromResult("Failed to bind parameter", &result);
             }
-            _handle = null;
-            _disposed = true;
         }
-        GC.SuppressFinalize(this);
     }
 
-    ~TursoStatement()
+    /// <summary>
+    /// Bind a list of strings as a JSON array, to be expanded with json_each rather than
+    /// carray. Null strings become NULL elements.
+    /// </summary>
+    public void BindStringArray(int parameterIndex, IReadOnlyList<string?> values)
     {
-        Dispose();
+        ObjectDisposedException.ThrowIf(_disposed, this);
+        ArgumentNullException.ThrowIfNull(values);
+
+        var valuePtrs = new IntPtr[values.Count];
+        try
+        {
+            for (var i = 0; i < values.Count; i++)
+            {
+                valuePtrs[i] = values[i] == null ? IntPtr.Zero : Marshal.StringToCoTaskMemUTF8(values[i]);
+            }
+
+            fixed (IntPtr* valuesPtr = valuePtrs)
+            {
+                var result = TursoFFI.turso_statement_bind_text_array(_handle, parameterIndex, (byte**)valuesPtr, values.Count);
+                if (!result.success)
+                {
+                    throw TursoException.FromResult("Failed to bind parameter", &result);
+                }
+            }
+        }
+        finally
+        {
+            foreach (var valuePtr in valuePtrs)
+            {
+                Marshal.FreeCoTaskMem(valuePtr);
+            }
+        }
     }
 
-    private static string GetErrorMessage(TursoFFIResult result)
+    /// <summary>
+    /// Reset the statement to be executed again
+    /// </summary>
+    public void Reset()
     {
-        if (result.error_message == null)
-            return "Unknown error";
+        ObjectDisposedException.ThrowIf(_disposed, this);
 
-        try
+        var result = TursoFFI.turso_statement_reset(_handle);
+        if (!result.success)
         {
-            return Marshal.PtrToStringUTF8((IntPtr)result.error_message) ?? "Unknown error";
+            throw TursoException.FromResult("Failed to reset statement", &result);
         }
-        catch
+    }
+
+    /// <summary>
+    /// Dispose the statement and free associated resources
+    /// </summary>
+    public void Dispose()
+    {
+        Dispose(throwOnError: true);
+        GC.SuppressFinalize(this);
+    }
+
+    ~TursoStatement()
+    {
+        // An exception on the finalizer thread would take the process down
+        Dispose(throwOnError: false);
+    }
+
+    private void Dispose(bool throwOnError)
+    {
+        if (!_disposed && _handle != null)
         {
-            return "Unknown error";
+            var result = TursoFFI.turso_statement_finalize(_handle);
+            _handle = null;
+            _disposed = true;
+            _connection.RemoveDependent();
+            if (!result.success)
+            {
+                var exception = TursoException.FromResult("Failed to finalize statement", &result);
+                if (throwOnError)
+                {
+                    throw exception;
+                }
+            }
         }
     }
 }
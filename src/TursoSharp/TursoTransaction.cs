@@ -0,0 +1,85 @@
+using System;
+using Turso.Native;
+
+namespace TursoSharp;
+
+/// <summary>
+/// A transaction owned by this object. Disposing it without calling <see cref="Commit"/> or
+/// <see cref="Rollback"/> applies its <see cref="TursoTransactionDropBehavior"/>, unless the
+/// transaction was already ended another way, e.g. by <see cref="TursoConnection.CommitTransaction"/>.
+/// </summary>
+public sealed unsafe class TursoTransaction : IDisposable
+{
+    private void* _handle;
+    private bool _disposed;
+    private readonly TursoConnection _connection;
+
+    internal TursoTransaction(void* handle, TursoConnection connection)
+    {
+        _handle = handle;
+        _connection = connection;
+        _connection.AddDependent();
+    }
+
+    /// <summary>
+    /// Commit the transaction
+    /// </summary>
+    public void Commit()
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+
+        var result = TursoFFI.turso_transaction_commit(_handle);
+        if (!result.success)
+        {
+            throw TursoException.FromResult("Failed to commit transaction", &result);
+        }
+    }
+
+    /// <summary>
+    /// Rollback the transaction
+    /// </summary>
+    public void Rollback()
+    {
+        ObjectDisposedException.ThrowIf(_disposed, this);
+
+        var result = TursoFFI.turso_transaction_rollback(_handle);
+        if (!result.success)
+        {
+            throw TursoException.FromResult("Failed to rollback transaction", &result);
+        }
+    }
+
+    /// <summary>
+    /// Finish the transaction according to its drop behavior if it is still open and free the handle
+    /// </summary>
+    public void Dispose()
+    {
+        Dispose(throwOnError: true);
+        GC.SuppressFinalize(this);
+    }
+
+    ~TursoTransaction()
+    {
+        // An exception on the finalizer thread would take the process down
+        Dispose(throwOnError: false);
+    }
+
+    private void Dispose(bool throwOnError)
+    {
+        if (!_disposed && _handle != null)
+        {
+            var result = TursoFFI.turso_transaction_finalize(_handle);
+            _handle = null;
+            _disposed = true;
+            _connection.RemoveDependent();
+            if (!result.success)
+            {
+                var exception = TursoException.FromResult("Failed to finalize transaction", &result);
+                if (throwOnError)
+                {
+                    throw exception;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,28 @@
+namespace TursoSharp;
+
+/// <summary>
+/// What happens to a <see cref="TursoTransaction"/> that is disposed without being committed or rolled back
+/// </summary>
+public enum TursoTransactionDropBehavior
+{
+    /// <summary>
+    /// Roll back the transaction. This is the default.
+    /// </summary>
+    Rollback = 0,
+
+    /// <summary>
+    /// Commit the transaction
+    /// </summary>
+    Commit = 1,
+
+    /// <summary>
+    /// Leave the transaction open on the connection
+    /// </summary>
+    Ignore = 2,
+
+    /// <summary>
+    /// Roll back the transaction and throw a <see cref="TursoException"/> from Dispose. When the
+    /// transaction is finalized by the garbage collector instead, it is only rolled back.
+    /// </summary>
+    Panic = 3
+}
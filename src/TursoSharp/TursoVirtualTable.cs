@@ -0,0 +1,142 @@
+namespace TursoSharp;
+
+/// <summary>
+/// A read-only virtual table module implemented in C#, registered with
+/// <see cref="TursoConnection.CreateModule"/>. <c>CREATE VIRTUAL TABLE name USING module(arguments)</c>
+/// asks it for a table, which SQL then reads through cursors.
+/// </summary>
+/// <remarks>
+/// Every callback runs while a statement on the registering connection is prepared or stepped, so
+/// it must not use the connection. An exception fails that statement.
+/// </remarks>
+public abstract class TursoVirtualTableModule
+{
+    /// <summary>
+    /// Create the table for a <c>CREATE VIRTUAL TABLE</c> statement
+    /// </summary>
+    /// <param name="arguments">The module arguments, as written in the statement</param>
+    /// <returns>The table</returns>
+    public abstract TursoVirtualTable Connect(IReadOnlyList<string> arguments);
+
+    /// <summary>
+    /// Choose how to scan a table for a query, given its <c>WHERE</c> constraints. Set
+    /// <see cref="TursoVirtualTableConstraint.ArgumentIndex"/> on the constraints the plan uses so
+    /// their values reach <see cref="TursoVirtualTableCursor.Filter"/>. The default keeps a full scan.
+    /// </summary>
+    /// <param name="constraints">The constraints on the table's columns</param>
+    /// <returns>The plan, or null for a full scan</returns>
+    public virtual TursoVirtualTablePlan? BestIndex(IReadOnlyList<TursoVirtualTableConstraint> constraints) => null;
+}
+
+/// <summary>
+/// A table created by a <see cref="TursoVirtualTableModule"/>
+/// </summary>
+public abstract class TursoVirtualTable
+{
+    /// <summary>
+    /// The table's columns, as a <c>CREATE TABLE</c> statement whose table name is ignored
+    /// </summary>
+    public abstract string Schema { get; }
+
+    /// <summary>
+    /// Open a cursor to read the table with
+    /// </summary>
+    /// <returns>The cursor</returns>
+    public abstract TursoVirtualTableCursor Open();
+
+    /// <summary>
+    /// Called once the engine has dropped the table
+    /// </summary>
+    public virtual void Disconnect()
+    {
+    }
+}
+
+/// <summary>
+/// A scan over the rows of a <see cref="TursoVirtualTable"/>
+/// </summary>
+public abstract class TursoVirtualTableCursor
+{
+    /// <summary>
+    /// Start a scan and move to its first row
+    /// </summary>
+    /// <param name="indexNumber">The <see cref="TursoVirtualTablePlan.IndexNumber"/> of the chosen plan, 0 for a full scan</param>
+    /// <param name="arguments">The values of the constraints the plan uses, in <see cref="TursoVirtualTableConstraint.ArgumentIndex"/> order</param>
+    public abstract void Filter(int indexNumber, object?[] arguments);
+
+    /// <summary>
+    /// Move to the next row
+    /// </summary>
+    public abstract void Next();
+
+    /// <summary>
+    /// Whether the cursor has moved past the last row
+    /// </summary>
+    public abstract bool Eof { get; }
+
+    /// <summary>
+    /// Read a column of the current row, as a long, double, string, byte[] or null
+    /// </summary>
+    /// <param name="column">The column index</param>
+    /// <returns>The value</returns>
+    public abstract object? Column(int column);
+
+    /// <summary>
+    /// The rowid of the current row
+    /// </summary>
+    public abstract long RowId { get; }
+
+    /// <summary>
+    /// Called once the engine is done with the cursor
+    /// </summary>
+    public virtual void Close()
+    {
+    }
+}
+
+/// <summary>
+/// A <c>WHERE</c> constraint offered to <see cref="TursoVirtualTableModule.BestIndex"/>
+/// </summary>
+public sealed class TursoVirtualTableConstraint
+{
+    internal TursoVirtualTableConstraint(int column, int op, bool usable)
+    {
+        Column = column;
+        Operator = op;
+        Usable = usable;
+    }
+
+    /// <summary>
+    /// The constrained column
+    /// </summary>
+    public int Column { get; }
+
+    /// <summary>
+    /// The operator, numbered like SQLite's SQLITE_INDEX_CONSTRAINT_* values, e.g. 2 for equality
+    /// </summary>
+    public int Operator { get; }
+
+    /// <summary>
+    /// Whether the plan can use the constraint
+    /// </summary>
+    public bool Usable { get; }
+
+    /// <summary>
+    /// 1-based position of the constraint's value in the arguments passed to
+    /// <see cref="TursoVirtualTableCursor.Filter"/>, or 0 if the plan doesn't use it
+    /// </summary>
+    public int ArgumentIndex { get; set; }
+
+    /// <summary>
+    /// Whether the cursor guarantees the constraint, so the engine doesn't check it again
+    /// </summary>
+    public bool Omit { get; set; }
+}
+
+/// <summary>
+/// The scan chosen by <see cref="TursoVirtualTableModule.BestIndex"/>
+/// </summary>
+/// <param name="IndexNumber">Passed to <see cref="TursoVirtualTableCursor.Filter"/> to say which plan was chosen</param>
+/// <param name="EstimatedCost">The estimated cost of the scan</param>
+/// <param name="EstimatedRows">The estimated number of rows the scan returns</param>
+public readonly record struct TursoVirtualTablePlan(int IndexNumber, double EstimatedCost, long EstimatedRows);
@@ -0,0 +1,210 @@
+using System;
+using System.Globalization;
+using System.Runtime.CompilerServices;
+using System.Runtime.InteropServices;
+using System.Text;
+using Turso.Native;
+
+namespace TursoSharp;
+
+/// <summary>
+/// Native callbacks for virtual table modules implemented in C#. The user data of a registration is a
+/// GCHandle to the TursoVirtualTableModule, kept alive by the connection that registered it; tables
+/// and cursors are GCHandles freed by their disconnect and close callbacks.
+/// </summary>
+internal static unsafe class TursoVirtualTables
+{
+    [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+    internal static void* Connect(void* userData, void* context, int argc, void** argv)
+    {
+        try
+        {
+            var module = (TursoVirtualTableModule)GCHandle.FromIntPtr((IntPtr)userData).Target!;
+            var arguments = Array.ConvertAll(
+                TursoFunctions.ReadArguments(argc, argv),
+                argument => Convert.ToString(argument, CultureInfo.InvariantCulture) ?? string.Empty);
+
+            var table = module.Connect(arguments);
+            if (!Declare(context, table.Schema))
+            {
+                table.Disconnect();
+                return null;
+            }
+            return (void*)GCHandle.ToIntPtr(GCHandle.Alloc(table));
+        }
+        catch (Exception)
+        {
+            // The engine fails CREATE VIRTUAL TABLE when no table comes back
+            return null;
+        }
+    }
+
+    private static bool Declare(void* context, string schema)
+    {
+        var schemaBytes = Encoding.UTF8.GetBytes(schema + '\0');
+        fixed (byte* schemaPtr = schemaBytes)
+        {
+            var result = TursoFFI.turso_vtab_declare(context, schemaPtr);
+            if (!result.success)
+            {
+                TursoFFI.turso_free_error_message(&result);
+                return false;
+            }
+        }
+        return true;
+    }
+
+    [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+    internal static int BestIndex(void* userData, TursoIndexConstraint* constraints, int constraintCount, TursoIndexPlan* plan)
+    {
+        try
+        {
+            var module = (TursoVirtualTableModule)GCHandle.FromIntPtr((IntPtr)userData).Target!;
+            var offered = new TursoVirtualTableConstraint[constraintCount];
+            for (var i = 0; i < constraintCount; i++)
+            {
+                offered[i] = new TursoVirtualTableConstraint(constraints[i].column, constraints[i].op, constraints[i].usable);
+            }
+
+            // Anything other than zero keeps the full scan
+            if (module.BestIndex(offered) is not { } chosen)
+            {
+                return 1;
+            }
+            for (var i = 0; i < constraintCount; i++)
+            {
+                constraints[i].argv_index = offered[i].ArgumentIndex;
+                constraints[i].omit = offered[i].Omit;
+            }
+            plan->idx_num = chosen.IndexNumber;
+            plan->estimated_cost = chosen.EstimatedCost;
+            plan->estimated_rows = chosen.EstimatedRows;
+            return 0;
+        }
+        catch (Exception)
+        {
+            return 1;
+        }
+    }
+
+    [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+    internal static void Disconnect(void* table)
+    {
+        var handle = GCHandle.FromIntPtr((IntPtr)table);
+        try
+        {
+            ((TursoVirtualTable)handle.Target!).Disconnect();
+        }
+        catch (Exception)
+        {
+            // The table is gone either way
+        }
+        finally
+        {
+            handle.Free();
+        }
+    }
+
+    [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+    internal static void* Open(void* table)
+    {
+        try
+        {
+            var cursor = ((TursoVirtualTable)GCHandle.FromIntPtr((IntPtr)table).Target!).Open();
+            return (void*)GCHandle.ToIntPtr(GCHandle.Alloc(cursor));
+        }
+        catch (Exception)
+        {
+            return null;
+        }
+    }
+
+    [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+    internal static void Close(void* cursor)
+    {
+        var handle = GCHandle.FromIntPtr((IntPtr)cursor);
+        try
+        {
+            ((TursoVirtualTableCursor)handle.Target!).Close();
+        }
+        catch (Exception)
+        {
+            // The cursor is gone either way
+        }
+        finally
+        {
+            handle.Free();
+        }
+    }
+
+    [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+    internal static int Filter(void* cursor, int indexNumber, int argc, void** argv)
+    {
+        try
+        {
+            Target(cursor).Filter(indexNumber, TursoFunctions.ReadArguments(argc, argv));
+            return 0;
+        }
+        catch (Exception)
+        {
+            return 1;
+        }
+    }
+
+    [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+    internal static int Next(void* cursor)
+    {
+        try
+        {
+            Target(cursor).Next();
+            return 0;
+        }
+        catch (Exception)
+        {
+            return 1;
+        }
+    }
+
+    // Returns the native bool as a byte, a cursor that throws counting as finished
+    [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+    internal static byte Eof(void* cursor)
+    {
+        try
+        {
+            return Target(cursor).Eof ? (byte)1 : (byte)0;
+        }
+        catch (Exception)
+        {
+            return 1;
+        }
+    }
+
+    [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+    internal static void Column(void* cursor, void* context, int column)
+    {
+        try
+        {
+            TursoFunctions.SetResult(context, Target(cursor).Column(column));
+        }
+        catch (Exception ex)
+        {
+            TursoFunctions.SetError(context, ex.Message);
+        }
+    }
+
+    [UnmanagedCallersOnly(CallConvs = new[] { typeof(CallConvCdecl) })]
+    internal static long RowId(void* cursor)
+    {
+        try
+        {
+            return Target(cursor).RowId;
+        }
+        catch (Exception)
+        {
+            return 0;
+        }
+    }
+
+    private static TursoVirtualTableCursor Target(void* cursor) =>
+        (TursoVirtualTableCursor)GCHandle.FromIntPtr((IntPtr)cursor).Target!;
+}